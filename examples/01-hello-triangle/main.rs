@@ -1,18 +1,14 @@
 use gl::types::*;
 use gltut::glutil;
 use gltut::glutil::types::*;
-use gltut::glutil::{GlProgram, GlShader};
+use gltut::glutil::GlProgram;
 
 use anyhow::Context;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // SAFETY: do not drop window
     let (event_loop, window, gl_context, surface) = unsafe { gltut::init_window_and_context()? };
-    let triangles = TriangleExample::new();
-
-    let mut app = gltut::app::GlAppBuilder::new()
-        .with_display(|| triangles.display())
-        .build(window, gl_context, surface);
+    let mut app = gltut::app::GlApp::new(scene(), window, gl_context, surface);
 
     // run event loop
     event_loop
@@ -22,6 +18,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Sets up the example's OpenGL state, returning the delegate which renders it.
+///
+/// Must be called after the OpenGL context has been made current.
+pub fn scene() -> impl gltut::app::GlAppDelegate {
+    let triangles = TriangleExample::new();
+    gltut::app::GlAppBuilder::new().with_display(move || triangles.display())
+}
+
 /// Positions of the triangle vertices in homogeneous coordinates.
 #[rustfmt::skip]
 const VTX_POSITIONS: [f32; 12] = [
@@ -79,12 +83,8 @@ impl TriangleExample {
 }
 
 fn init_program() -> GlProgram {
-    let mut shader_list = Vec::with_capacity(2);
-    shader_list.push(GlShader::compile_unwrap(GlShaderType::Vertex, VERT_SHADER));
-    shader_list.push(GlShader::compile_unwrap(
-        GlShaderType::Fragment,
-        FRAG_SHADER,
-    ));
-
-    return GlProgram::link_unwrap(&shader_list);
+    return GlProgram::build_or_fallback(&[
+        (GlShaderType::Vertex, VERT_SHADER),
+        (GlShaderType::Fragment, FRAG_SHADER),
+    ]);
 }
@@ -1,6 +1,6 @@
 use gltut::glutil;
 use gltut::glutil::types::*;
-use gltut::glutil::{GlProgram, GlShader};
+use gltut::glutil::GlProgram;
 
 use anyhow::Context;
 use gl::types::*;
@@ -8,11 +8,24 @@ use gl::types::*;
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // SAFETY: do not drop window
     let (event_loop, window, gl_context, surface) = unsafe { gltut::init_window_and_context()? };
+    let mut app = gltut::app::GlApp::new(scene(), window, gl_context, surface);
 
+    // run event loop
+    event_loop
+        .run_app(&mut app)
+        .context("failed to start event_loop")?;
+
+    Ok(())
+}
+
+/// Sets up the example's OpenGL state, returning the delegate which renders it.
+///
+/// Must be called after the OpenGL context has been made current.
+pub fn scene() -> impl gltut::app::GlAppDelegate {
     let render_ygrad = get_ygrad_render_fn();
     let render_tricolor = get_tricolor_render_fn();
-    let mut app = gltut::app::GlAppBuilder::new()
-        .with_display(|| {
+    gltut::app::GlAppBuilder::new()
+        .with_display(move || {
             unsafe {
                 gl::ClearColor(0.0, 0.0, 0.0, 0.0);
                 gl::Clear(gl::COLOR_BUFFER_BIT);
@@ -23,14 +36,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             render_tricolor();
         })
         .with_reshape(centered_reshape)
-        .build(window, gl_context, surface);
-
-    // run event loop
-    event_loop
-        .run_app(&mut app)
-        .context("failed to start event_loop")?;
-
-    Ok(())
 }
 
 /// Set viewport to the largest centered square box that can fit in the window dimensions
@@ -80,17 +85,10 @@ fn get_ygrad_render_fn() -> impl Fn() {
 }
 
 fn init_ygrad_program() -> GlProgram {
-    let mut shader_list = Vec::with_capacity(2);
-    shader_list.push(GlShader::compile_unwrap(
-        GlShaderType::Vertex,
-        YGRAD_VERT_SHADER,
-    ));
-    shader_list.push(GlShader::compile_unwrap(
-        GlShaderType::Fragment,
-        YGRAD_FRAG_SHADER,
-    ));
-
-    return GlProgram::link_unwrap(&shader_list);
+    return GlProgram::build_or_fallback(&[
+        (GlShaderType::Vertex, YGRAD_VERT_SHADER),
+        (GlShaderType::Fragment, YGRAD_FRAG_SHADER),
+    ]);
 }
 
 fn init_ygrad_vao() -> GLuint {
@@ -144,17 +142,10 @@ fn get_tricolor_render_fn() -> impl Fn() {
 }
 
 fn init_tricolor_program() -> GlProgram {
-    let mut shader_list = Vec::with_capacity(2);
-    shader_list.push(GlShader::compile_unwrap(
-        GlShaderType::Vertex,
-        &TRICOLOR_VERT_SHADER,
-    ));
-    shader_list.push(GlShader::compile_unwrap(
-        GlShaderType::Fragment,
-        &TRICOLOR_FRAG_SHADER,
-    ));
-
-    return GlProgram::link_unwrap(&shader_list);
+    return GlProgram::build_or_fallback(&[
+        (GlShaderType::Vertex, TRICOLOR_VERT_SHADER),
+        (GlShaderType::Fragment, TRICOLOR_FRAG_SHADER),
+    ]);
 }
 
 fn init_tricolor_vao() -> GLuint {
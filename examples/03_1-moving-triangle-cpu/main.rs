@@ -1,8 +1,6 @@
-use std::time::Instant;
-
 use gltut::glutil;
 use gltut::glutil::types::*;
-use gltut::glutil::{GlProgram, GlShader};
+use gltut::glutil::GlProgram;
 
 use anyhow::Context;
 use gl::types::*;
@@ -10,10 +8,7 @@ use gl::types::*;
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // SAFETY: do not drop window
     let (event_loop, window, gl_context, surface) = unsafe { gltut::init_window_and_context()? };
-
-    use_program();
-    let triangle = MovingTriangle::new();
-    let mut app = gltut::app::GlApp::new(triangle, window, gl_context, surface);
+    let mut app = gltut::app::GlApp::new(scene(), window, gl_context, surface);
 
     // run event loop
     event_loop
@@ -23,6 +18,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Sets up the example's OpenGL state, returning the delegate which renders it.
+///
+/// Must be called after the OpenGL context has been made current.
+pub fn scene() -> impl gltut::app::GlAppDelegate {
+    use_program();
+    MovingTriangle::new()
+}
+
 #[rustfmt::skip]
 const VTX_DATA: [f32; 12] = [
     0.25, 0.25, 0.0, 1.0,
@@ -37,7 +40,6 @@ struct MovingTriangle {
     vtx_positions: Vec<f32>,
     position_buf_object: GLuint,
     vao: GLuint,
-    start: Instant,
 }
 
 /// Renders a triangle moving counter-clockwise in a circle
@@ -63,7 +65,6 @@ impl MovingTriangle {
             vtx_positions,
             position_buf_object,
             vao,
-            start: Instant::now(),
         }
     }
 
@@ -99,9 +100,7 @@ impl MovingTriangle {
 
 impl gltut::app::GlAppDelegate for MovingTriangle {
     fn display(&mut self, app: &gltut::app::GlAppContext) {
-        let t = std::time::Instant::now()
-            .duration_since(self.start)
-            .as_millis() as u32;
+        let t = app.clock.elapsed().as_millis() as u32;
         self.adjust_vtx_data(t);
 
         unsafe {
@@ -123,14 +122,10 @@ const FRAG_SHADER: &'static str = include_str!("./shaders/flat-color.frag");
 
 /// Compiles an OpenGL program to use globally
 fn use_program() {
-    let mut shader_list = Vec::with_capacity(2);
-    shader_list.push(GlShader::compile_unwrap(GlShaderType::Vertex, &VERT_SHADER));
-    shader_list.push(GlShader::compile_unwrap(
-        GlShaderType::Fragment,
-        &FRAG_SHADER,
-    ));
-
-    let program = GlProgram::link_unwrap(&shader_list);
+    let program = GlProgram::build_or_fallback(&[
+        (GlShaderType::Vertex, VERT_SHADER),
+        (GlShaderType::Fragment, FRAG_SHADER),
+    ]);
 
     unsafe {
         gl::UseProgram(program.handle());
@@ -1,9 +1,8 @@
 use std::ffi::CString;
-use std::time::Instant;
 
 use gltut::glutil;
 use gltut::glutil::types::*;
-use gltut::glutil::{GlProgram, GlShader};
+use gltut::glutil::GlProgram;
 
 use anyhow::Context;
 use gl::types::*;
@@ -11,9 +10,7 @@ use gl::types::*;
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // SAFETY: do not drop window
     let (event_loop, window, gl_context, surface) = unsafe { gltut::init_window_and_context()? };
-
-    let triangle = MovingTriangle::new();
-    let mut app = gltut::app::GlApp::new(triangle, window, gl_context, surface);
+    let mut app = gltut::app::GlApp::new(scene(), window, gl_context, surface);
 
     // run event loop
     event_loop
@@ -23,6 +20,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Sets up the example's OpenGL state, returning the delegate which renders it.
+///
+/// Must be called after the OpenGL context has been made current.
+pub fn scene() -> impl gltut::app::GlAppDelegate {
+    MovingTriangle::new()
+}
+
 #[rustfmt::skip]
 const VTX_DATA: [f32; 12] = [
     0.25, 0.25, 0.0, 1.0,
@@ -38,7 +42,6 @@ struct MovingTriangle {
     /// Location for updating the vertex translation uniform
     offset_location: GLint,
     vao: GLuint,
-    start: Instant,
 }
 
 /// Renders a triangle moving counter-clockwise in a circle
@@ -50,16 +53,14 @@ impl MovingTriangle {
             program,
             offset_location,
             vao,
-            start: Instant::now(),
         }
     }
 }
 
 impl gltut::app::GlAppDelegate for MovingTriangle {
     fn display(&mut self, app: &gltut::app::GlAppContext) {
-        let t = std::time::Instant::now()
-            .duration_since(self.start)
-            .as_millis() as u32;
+        // use the app clock so the animation follows the console's timescale
+        let t = app.clock.elapsed().as_millis() as u32;
         let (dx, dy) = get_offset(t);
 
         unsafe {
@@ -106,15 +107,11 @@ const FRAG_SHADER: &'static str = include_str!("./shaders/cycle-color.frag");
 
 /// Compiles an OpenGL program to use globally
 fn init_program() -> (GlProgram, GLint) {
-    let mut shader_list = Vec::with_capacity(2);
-    shader_list.push(GlShader::compile_unwrap(GlShaderType::Vertex, &VERT_SHADER));
-    shader_list.push(GlShader::compile_unwrap(
-        GlShaderType::Fragment,
-        &FRAG_SHADER,
-    ));
-
     let offset_name = CString::new("offset").unwrap();
-    let program = GlProgram::link_unwrap(&shader_list);
+    let program = GlProgram::build_or_fallback(&[
+        (GlShaderType::Vertex, VERT_SHADER),
+        (GlShaderType::Fragment, FRAG_SHADER),
+    ]);
     let offset_location = unsafe { gl::GetUniformLocation(program.handle(), offset_name.as_ptr()) };
 
     (program, offset_location)
@@ -0,0 +1,159 @@
+use gltut::glutil;
+use gltut::glutil::types::*;
+use gltut::glutil::{GlProgram, GlShader};
+use gltut::math::Mat4;
+use gltut::scene::Scene;
+
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use gl::types::*;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // SAFETY: do not drop window
+    let (event_loop, window, gl_context, surface) = unsafe { gltut::init_window_and_context()? };
+    let mut app = gltut::app::GlApp::new(scene(), window, gl_context, surface);
+
+    // run event loop
+    event_loop
+        .run_app(&mut app)
+        .context("failed to start event_loop")?;
+
+    Ok(())
+}
+
+/// Scene graph rendered by the example, mesh paths inside it are relative to this file.
+const SCENE_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/examples/scene-from-file/world.ron"
+);
+
+/// Sets up the example's OpenGL state, returning the delegate which renders it.
+///
+/// Must be called after the OpenGL context has been made current.
+pub fn scene() -> impl gltut::app::GlAppDelegate {
+    let program = build_program();
+    let scene = Scene::load(SCENE_PATH).expect("failed to load scene file");
+    let base_dir = Path::new(SCENE_PATH).parent().unwrap();
+    SceneFromFile::new(program, &scene, base_dir)
+}
+
+/// A mesh uploaded to the GPU, shared by every node referencing the same file.
+struct Mesh {
+    vao: GLuint,
+    vertex_count: GLsizei,
+}
+
+impl Mesh {
+    /// Reads a mesh file containing one `x y z w` vertex position per line (lines starting with `#` are ignored),
+    /// where every three vertices form a triangle.
+    fn load(path: &Path) -> Self {
+        let contents = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("failed to read mesh {}: {}", path.display(), e));
+        let positions: Vec<f32> = contents
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(str::split_whitespace)
+            .map(|token| {
+                token.parse().unwrap_or_else(|_| {
+                    panic!("invalid number in mesh {}: {}", path.display(), token)
+                })
+            })
+            .collect();
+
+        let position_buf_object = glutil::init_vertex_buffer(&positions, GlBufUsage::StaticDraw);
+
+        let mut vao = 0;
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, position_buf_object);
+
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(0, 4, gl::FLOAT, gl::FALSE, 0, 0 as *const GLvoid);
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+
+        Self {
+            vao,
+            vertex_count: (positions.len() / 4) as GLsizei,
+        }
+    }
+}
+
+/// Renders every node of a scene graph loaded from a file, using its world transform as the model-to-clip matrix
+struct SceneFromFile {
+    _program: GlProgram,
+    model_to_clip_unif: GLint,
+    meshes: HashMap<PathBuf, Mesh>,
+    /// (mesh, world transform) of every node to draw, flattened from the scene graph
+    draw_list: Vec<(PathBuf, Mat4)>,
+}
+
+impl SceneFromFile {
+    fn new(program: GlProgram, scene: &Scene, base_dir: &Path) -> Self {
+        let model_to_clip_unif = unsafe {
+            let name = CString::new("modelToClip").unwrap();
+            gl::GetUniformLocation(program.handle(), name.as_ptr())
+        };
+
+        // the scene is static, so the world transforms only need to be computed once
+        let mut meshes = HashMap::new();
+        let mut draw_list = Vec::new();
+        scene.for_each_node(|node, world| {
+            if let Some(mesh) = &node.mesh {
+                let path = base_dir.join(mesh);
+                meshes
+                    .entry(path.clone())
+                    .or_insert_with(|| Mesh::load(&path));
+                draw_list.push((path, *world));
+            }
+        });
+
+        Self {
+            _program: program,
+            model_to_clip_unif,
+            meshes,
+            draw_list,
+        }
+    }
+}
+
+impl gltut::app::GlAppDelegate for SceneFromFile {
+    fn display(&mut self, _app: &gltut::app::GlAppContext) {
+        unsafe {
+            gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            for (path, world) in &self.draw_list {
+                let mesh = &self.meshes[path];
+                gl::UniformMatrix4fv(self.model_to_clip_unif, 1, gl::FALSE, world.as_ptr());
+                gl::BindVertexArray(mesh.vao);
+                gl::DrawArrays(gl::TRIANGLES, 0, mesh.vertex_count);
+            }
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+const VERT_SHADER: &'static str = include_str!("./shaders/transform.vert");
+const FRAG_SHADER: &'static str = include_str!("./shaders/flat-color.frag");
+
+/// Compiles the OpenGL program and uses it globally
+fn build_program() -> GlProgram {
+    let shader_list = [
+        GlShader::compile_unwrap(GlShaderType::Vertex, VERT_SHADER),
+        GlShader::compile_unwrap(GlShaderType::Fragment, FRAG_SHADER),
+    ];
+    let program = GlProgram::link_unwrap(&shader_list);
+
+    unsafe {
+        gl::UseProgram(program.handle());
+    }
+    program
+}
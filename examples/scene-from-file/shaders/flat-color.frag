@@ -0,0 +1,7 @@
+#version 330
+
+out vec4 outputColor;
+void main()
+{
+    outputColor = vec4(1.0f, 1.0f, 1.0f, 1.0f);
+}
@@ -0,0 +1,9 @@
+#version 330
+
+layout(location = 0) in vec4 position;
+uniform mat4 modelToClip;
+
+void main()
+{
+    gl_Position = modelToClip * position;
+}
@@ -14,6 +14,7 @@
 
 // Changes w.r.t. source work:
 //  - fixed registry version to 4.1
+//  - added the extensions probed by `GlInfo` (debug output and anisotropic filtering)
 
 use gl_generator::{Api, Fallbacks, GlobalGenerator, Profile, Registry};
 use std::env;
@@ -25,9 +26,9 @@ fn main() {
     let mut file = File::create(&Path::new(&dest).join("bindings.rs")).unwrap();
 
     // set version to 4.1
-    // TODO: check out extensions ["GL_KHR_debug", "GL_ARB_debug_output"]
-    //   if we ever trying building these on a non-MacOS system.
-    Registry::new(Api::Gl, (4, 1), Profile::Core, Fallbacks::All, [])
+    // extensions are only loaded if the driver supports them, so they are harmless on MacOS
+    let extensions = ["GL_KHR_debug", "GL_EXT_texture_filter_anisotropic"];
+    Registry::new(Api::Gl, (4, 1), Profile::Core, Fallbacks::All, extensions)
         .write_bindings(GlobalGenerator, &mut file)
         .unwrap();
 }
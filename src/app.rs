@@ -1,13 +1,21 @@
 //! Contains the standard structure for defining an [`ApplicationHandler`]
 //! to feed the [`EventLoop`](winit::event_loop::EventLoop).
 
+use std::num::NonZeroU32;
+use std::time::{Duration, Instant};
+
+use crate::console::{Console, ConsoleCommand, ConsoleError, RenderSettings};
+use crate::glutil::{self, GlError};
+
 use gl::types::*;
 use glutin::{
     context::PossiblyCurrentContext,
-    surface::{GlSurface, Surface, WindowSurface},
+    surface::{GlSurface, Surface, SwapInterval, WindowSurface},
 };
 use glutin_winit::GlWindow;
 use winit::dpi::PhysicalSize;
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::{Key, KeyCode, NamedKey, PhysicalKey};
 use winit::window::Window;
 use winit::{application::ApplicationHandler, event::WindowEvent};
 
@@ -44,14 +52,119 @@ fn set_gl_viewport(size: &PhysicalSize<u32>) {
     }
 }
 
+/// Tracks the application time, scaled by [`RenderSettings::timescale`].
+///
+/// It is advanced by [`GlApp`] once before every call to [`GlAppDelegate::display()`],
+/// so animations driven by [`Self::elapsed()`] can be slowed down or sped up from the console.
+///
+/// By default it follows the wall clock, but it can be switched to advance by a fixed step every frame
+/// (see [`GlApp::with_fixed_timestep()`]) so that rendering is deterministic.
+#[derive(Debug)]
+pub struct FrameClock {
+    source: ClockSource,
+    elapsed: Duration,
+}
+
+#[derive(Debug)]
+enum ClockSource {
+    Realtime { last_tick: Instant },
+    Fixed { step: Duration },
+}
+
+impl FrameClock {
+    fn new() -> Self {
+        Self {
+            source: ClockSource::Realtime {
+                last_tick: Instant::now(),
+            },
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    fn tick(&mut self, timescale: f32) {
+        let delta = match &mut self.source {
+            ClockSource::Realtime { last_tick } => {
+                let now = Instant::now();
+                let delta = now.duration_since(*last_tick);
+                *last_tick = now;
+                delta
+            }
+            ClockSource::Fixed { step } => *step,
+        };
+        self.elapsed += delta.mul_f32(timescale);
+    }
+
+    /// The (scaled) time elapsed since the application started.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
 /// Allows [`GlAppDelegate`] to access handles to the various window and OpenGL related structs.
 pub struct GlAppContext {
     pub window: Window,
     pub context: PossiblyCurrentContext,
     pub surface: Surface<WindowSurface>,
+    pub settings: RenderSettings,
+    pub clock: FrameClock,
     _private: (), // prevent external modules from instantiating this struct
 }
 
+impl GlAppContext {
+    /// Applies a console command to the corresponding subsystem, returning a message describing the result.
+    fn execute(&mut self, command: ConsoleCommand) -> Result<String, ConsoleError> {
+        match command {
+            ConsoleCommand::Vsync(enabled) => {
+                let interval = if enabled {
+                    SwapInterval::Wait(NonZeroU32::MIN)
+                } else {
+                    SwapInterval::DontWait
+                };
+                self.surface
+                    .set_swap_interval(&self.context, interval)
+                    .map_err(|e| ConsoleError::Unsupported(e.to_string()))?;
+                self.settings.vsync = enabled;
+            }
+            ConsoleCommand::Msaa(samples) => {
+                // the sample count is fixed when the surface is created, so it can only be toggled here
+                let available = get_surface_samples();
+                if samples != 0 && samples != available {
+                    return Err(ConsoleError::Unsupported(format!(
+                        "surface was created with {} samples, only msaa 0 or {} is supported",
+                        available, available
+                    )));
+                }
+                unsafe {
+                    if samples == 0 {
+                        gl::Disable(gl::MULTISAMPLE);
+                    } else {
+                        gl::Enable(gl::MULTISAMPLE);
+                    }
+                }
+                self.settings.msaa = samples;
+            }
+            ConsoleCommand::Timescale(scale) => self.settings.timescale = scale,
+            ConsoleCommand::Wireframe(enabled) => {
+                let mode = if enabled { gl::LINE } else { gl::FILL };
+                unsafe {
+                    gl::PolygonMode(gl::FRONT_AND_BACK, mode);
+                }
+                self.settings.wireframe = enabled;
+            }
+        }
+        Ok(format!("applied {:?}", command))
+    }
+}
+
+fn get_surface_samples() -> u32 {
+    let mut samples = 0;
+    unsafe {
+        gl::GetIntegerv(gl::SAMPLES, &mut samples);
+    }
+    samples as u32
+}
+
 /// An basic implementation of [`ApplicationHandler`].
 ///
 /// It uses a limited set of handlers to respond to some standard scenarios.
@@ -61,6 +174,9 @@ pub struct GlAppContext {
 pub struct GlApp<T> {
     delegate: T,
     app: GlAppContext,
+    console: Console,
+    /// Window title to restore once the console is closed.
+    title: String,
 }
 
 impl<T: GlAppDelegate> GlApp<T> {
@@ -70,13 +186,124 @@ impl<T: GlAppDelegate> GlApp<T> {
         context: PossiblyCurrentContext,
         surface: Surface<WindowSurface>,
     ) -> Self {
+        let settings = RenderSettings {
+            msaa: get_surface_samples(),
+            ..Default::default()
+        };
+        let title = window.title();
         let app = GlAppContext {
             window,
             context,
             surface,
+            settings,
+            clock: FrameClock::new(),
             _private: (),
         };
-        Self { delegate, app }
+        let mut result = Self {
+            delegate,
+            app,
+            console: Console::default(),
+            title,
+        };
+        // the driver's default swap interval varies, so apply the setting rather than assuming it already holds
+        result.set_vsync(result.app.settings.vsync);
+        result
+    }
+
+    /// Sets whether swapping buffers waits for vertical sync, like the `vsync` console command (enabled by default).
+    ///
+    /// Disabling it lets automated tests render frames as fast as possible.
+    pub fn with_vsync(mut self, enabled: bool) -> Self {
+        self.set_vsync(enabled);
+        self
+    }
+
+    fn set_vsync(&mut self, enabled: bool) {
+        if let Err(e) = self.app.execute(ConsoleCommand::Vsync(enabled)) {
+            eprintln!("warning: failed to set vsync: {}", e);
+        }
+    }
+
+    /// Switches the app clock to advance by exactly `step` every frame, regardless of how long frames actually take.
+    pub fn with_fixed_timestep(mut self, step: Duration) -> Self {
+        self.app.clock.source = ClockSource::Fixed { step };
+        self
+    }
+
+    /// Renders `frames` frames back to back without running the event loop, checking for OpenGL errors after each one.
+    ///
+    /// Intended for automated testing, together with [`init_hidden_window_and_context()`](crate::init_hidden_window_and_context)
+    /// and [`Self::with_fixed_timestep()`].
+    pub fn render_frames(&mut self, frames: usize) -> Result<(), GlError> {
+        // give the delegate a chance to set up its viewport, like it would on a real window
+        let size = self.app.window.inner_size();
+        self.delegate.reshape(&self.app, &size);
+        glutil::check_error()?;
+
+        for _ in 0..frames {
+            self.redraw();
+            glutil::check_error()?;
+        }
+        Ok(())
+    }
+
+    /// Advances the clock, calls the delegate to render the frame and presents the result.
+    fn redraw(&mut self) {
+        self.app.clock.tick(self.app.settings.timescale);
+
+        // call user-specified display function
+        self.delegate.display(&self.app);
+
+        // render the results
+        unsafe {
+            gl::Flush();
+        }
+        self.app.window.pre_present_notify();
+        // NOTE: swap buffers is important, can get get doubled buffered surface even when single buffered is requested
+        self.app
+            .surface
+            .swap_buffers(&self.app.context)
+            .expect("failed to swap GLSurface buffers");
+    }
+
+    /// Handles key presses for the console, which is toggled with the backtick key.
+    fn handle_console_key(&mut self, event: &KeyEvent) {
+        if event.state != ElementState::Pressed {
+            return;
+        }
+
+        if event.physical_key == PhysicalKey::Code(KeyCode::Backquote) {
+            self.console.toggle();
+        } else if self.console.is_open() {
+            match &event.logical_key {
+                Key::Named(NamedKey::Enter) => {
+                    let status = self
+                        .console
+                        .submit()
+                        .and_then(|command| self.app.execute(command))
+                        .unwrap_or_else(|e| format!("error: {}", e));
+                    eprintln!("console: {}", status);
+                    self.console.set_status(status);
+                    // with `ControlFlow::Wait` nothing else would redraw the window to show the new settings
+                    self.app.window.request_redraw();
+                }
+                Key::Named(NamedKey::Backspace) => self.console.backspace(),
+                Key::Named(NamedKey::Escape) => self.console.toggle(),
+                _ => {
+                    if let Some(text) = &event.text {
+                        self.console.push_str(text);
+                    }
+                }
+            }
+        } else {
+            return;
+        }
+
+        if self.console.is_open() {
+            self.app.window.set_title(&self.console.display_text());
+        } else {
+            self.app.window.set_title(&self.title);
+        }
     }
 }
 
@@ -92,27 +319,14 @@ impl<T: GlAppDelegate> ApplicationHandler for GlApp<T> {
         match event {
             // stop the application once user closes the window
             WindowEvent::CloseRequested => event_loop.exit(),
-            WindowEvent::RedrawRequested => {
-                // call user-specified display function
-                self.delegate.display(&self.app);
-
-                // render the results
-                unsafe {
-                    gl::Flush();
-                }
-                self.app.window.pre_present_notify();
-                // NOTE: swap buffers is important, can get get doubled buffered surface even when single buffered is requested
-                self.app
-                    .surface
-                    .swap_buffers(&self.app.context)
-                    .expect("failed to swap GLSurface buffers");
-            }
+            WindowEvent::RedrawRequested => self.redraw(),
             WindowEvent::Resized(size) => {
                 self.app
                     .window
                     .resize_surface(&self.app.surface, &self.app.context);
                 self.delegate.reshape(&self.app, &size);
             }
+            WindowEvent::KeyboardInput { event, .. } => self.handle_console_key(&event),
             _ => (),
         };
     }
@@ -0,0 +1,256 @@
+//! Contains a tiny command console for tweaking render settings while an application is running.
+//!
+//! The console is toggled with the backtick key by [`GlApp`](crate::app::GlApp).
+//! Since there is no text rendering yet, the input line and command results are shown in the window title
+//! (and results are also echoed to stderr).
+//!
+//! Supported commands:
+//!
+//! - `vsync on|off`: wait for vertical sync when swapping buffers
+//! - `msaa <samples>`: toggle multisampling, `0` disables it and any other value must equal the samples the surface was created with
+//! - `timescale <factor>`: speed up or slow down the application clock, by a factor between `0` and `100`
+//! - `wireframe on|off`: rasterize polygons as lines
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// Largest factor accepted by the `timescale` command, beyond which scaled frame times could overflow a `Duration`.
+const MAX_TIMESCALE: f32 = 100.0;
+
+/// A parsed console command.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ConsoleCommand {
+    Vsync(bool),
+    Msaa(u32),
+    Timescale(f32),
+    Wireframe(bool),
+}
+
+impl FromStr for ConsoleCommand {
+    type Err = ConsoleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        let name = tokens.next().ok_or(ConsoleError::Empty)?;
+        let arg = tokens
+            .next()
+            .ok_or_else(|| ConsoleError::MissingArgument(name.into()))?;
+        if let Some(extra) = tokens.next() {
+            return Err(ConsoleError::InvalidArgument(extra.into()));
+        }
+
+        match name {
+            "vsync" => Ok(ConsoleCommand::Vsync(parse_toggle(arg)?)),
+            "msaa" => arg
+                .parse()
+                .map(ConsoleCommand::Msaa)
+                .map_err(|_| ConsoleError::InvalidArgument(arg.into())),
+            "timescale" => arg
+                .parse()
+                .ok()
+                .filter(|scale: &f32| (0.0..=MAX_TIMESCALE).contains(scale))
+                .map(ConsoleCommand::Timescale)
+                .ok_or_else(|| ConsoleError::InvalidArgument(arg.into())),
+            "wireframe" => Ok(ConsoleCommand::Wireframe(parse_toggle(arg)?)),
+            _ => Err(ConsoleError::UnknownCommand(name.into())),
+        }
+    }
+}
+
+fn parse_toggle(arg: &str) -> Result<bool, ConsoleError> {
+    match arg {
+        "on" | "1" | "true" => Ok(true),
+        "off" | "0" | "false" => Ok(false),
+        _ => Err(ConsoleError::InvalidArgument(arg.into())),
+    }
+}
+
+/// Runtime-configurable render settings which can be changed through the console.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RenderSettings {
+    pub vsync: bool,
+    /// Number of samples used for multisampling, `0` if disabled.
+    pub msaa: u32,
+    /// Factor applied to the application clock, see [`FrameClock`](crate::app::FrameClock).
+    pub timescale: f32,
+    pub wireframe: bool,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            vsync: true,
+            msaa: 0,
+            timescale: 1.0,
+            wireframe: false,
+        }
+    }
+}
+
+/// The input state of the console.
+#[derive(Debug, Default)]
+pub struct Console {
+    open: bool,
+    input: String,
+    status: String,
+}
+
+impl Console {
+    /// Whether the console is currently capturing keyboard input.
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Opens or closes the console, discarding any partially typed command.
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.input.clear();
+        self.status.clear();
+    }
+
+    /// Appends typed text to the input line, ignoring control characters.
+    pub fn push_str(&mut self, text: &str) {
+        self.input.extend(text.chars().filter(|c| !c.is_control()));
+    }
+
+    /// Removes the last character of the input line.
+    pub fn backspace(&mut self) {
+        self.input.pop();
+    }
+
+    /// Clears the input line and parses it as a command.
+    pub fn submit(&mut self) -> Result<ConsoleCommand, ConsoleError> {
+        let line = std::mem::take(&mut self.input);
+        line.parse()
+    }
+
+    /// Sets the message shown next to the input line, e.g. the result of the last command.
+    pub fn set_status(&mut self, status: String) {
+        self.status = status;
+    }
+
+    /// The text to display for the console.
+    pub fn display_text(&self) -> String {
+        if self.status.is_empty() {
+            format!("> {}", self.input)
+        } else {
+            format!("[{}] > {}", self.status, self.input)
+        }
+    }
+}
+
+/// Errors that can occur when parsing or executing a [`ConsoleCommand`].
+#[derive(Debug, Error)]
+pub enum ConsoleError {
+    #[error("no command entered")]
+    Empty,
+    #[error("unknown command: {0}")]
+    UnknownCommand(String),
+    #[error("missing argument for {0}")]
+    MissingArgument(String),
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("{0}")]
+    Unsupported(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<ConsoleCommand, ConsoleError> {
+        s.parse()
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(parse("vsync off").unwrap(), ConsoleCommand::Vsync(false));
+        assert_eq!(parse("msaa 4").unwrap(), ConsoleCommand::Msaa(4));
+        assert_eq!(
+            parse("  timescale   0.5 ").unwrap(),
+            ConsoleCommand::Timescale(0.5)
+        );
+        assert_eq!(
+            parse("timescale 0").unwrap(),
+            ConsoleCommand::Timescale(0.0)
+        );
+        assert_eq!(
+            parse("timescale 100").unwrap(),
+            ConsoleCommand::Timescale(100.0)
+        );
+        assert_eq!(
+            parse("wireframe on").unwrap(),
+            ConsoleCommand::Wireframe(true)
+        );
+    }
+
+    #[test]
+    fn toggle_spellings() {
+        for arg in ["on", "1", "true"] {
+            assert_eq!(
+                parse(&format!("vsync {}", arg)).unwrap(),
+                ConsoleCommand::Vsync(true)
+            );
+        }
+        for arg in ["off", "0", "false"] {
+            assert_eq!(
+                parse(&format!("vsync {}", arg)).unwrap(),
+                ConsoleCommand::Vsync(false)
+            );
+        }
+        assert!(matches!(
+            parse("wireframe yes"),
+            Err(ConsoleError::InvalidArgument(arg)) if arg == "yes"
+        ));
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(matches!(parse(""), Err(ConsoleError::Empty)));
+        assert!(matches!(parse("   "), Err(ConsoleError::Empty)));
+    }
+
+    #[test]
+    fn missing_argument() {
+        assert!(matches!(
+            parse("msaa"),
+            Err(ConsoleError::MissingArgument(name)) if name == "msaa"
+        ));
+    }
+
+    #[test]
+    fn extra_token() {
+        assert!(matches!(
+            parse("vsync on now"),
+            Err(ConsoleError::InvalidArgument(arg)) if arg == "now"
+        ));
+    }
+
+    #[test]
+    fn unknown_command() {
+        assert!(matches!(
+            parse("fullscreen on"),
+            Err(ConsoleError::UnknownCommand(name)) if name == "fullscreen"
+        ));
+    }
+
+    #[test]
+    fn invalid_timescale() {
+        for arg in ["-1", "NaN", "inf", "1e30", "100.5", "fast"] {
+            assert!(matches!(
+                parse(&format!("timescale {}", arg)),
+                Err(ConsoleError::InvalidArgument(a)) if a == arg
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_msaa() {
+        assert!(matches!(
+            parse("msaa -4"),
+            Err(ConsoleError::InvalidArgument(arg)) if arg == "-4"
+        ));
+    }
+}
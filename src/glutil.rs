@@ -1,10 +1,20 @@
 //! Contains utility functions for some common OpenGL operations.
 
 use gl::types::*;
+use thiserror::Error;
 
 mod shader;
 pub use shader::*;
 
+mod framebuffer;
+pub use framebuffer::*;
+
+mod info;
+pub use info::*;
+
+pub mod cubemap;
+pub mod depth_peel;
+
 pub mod types;
 use types::*;
 
@@ -26,3 +36,36 @@ pub fn init_vertex_buffer(vtx_data: &[f32], usage: GlBufUsage) -> GLuint {
     }
     return vtx_buffer_object;
 }
+
+/// Checks for errors recorded by OpenGL using `glGetError`.
+///
+/// If several errors have been recorded, only the first is returned and the rest are cleared.
+pub fn check_error() -> Result<(), GlError> {
+    let code = unsafe { gl::GetError() };
+    if code == gl::NO_ERROR {
+        return Ok(());
+    }
+
+    // drain the remaining error flags so they are not misattributed to a later check
+    while unsafe { gl::GetError() } != gl::NO_ERROR {}
+    Err(GlError { code })
+}
+
+/// Represents an error flag reported by `glGetError`.
+#[derive(Debug, Error)]
+#[error("OpenGL error {} (0x{:04X})", get_error_name(*.code), .code)]
+pub struct GlError {
+    code: GLenum,
+}
+
+#[inline]
+const fn get_error_name(code: GLenum) -> &'static str {
+    match code {
+        gl::INVALID_ENUM => "GL_INVALID_ENUM",
+        gl::INVALID_VALUE => "GL_INVALID_VALUE",
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        _ => "unknown",
+    }
+}
@@ -0,0 +1,119 @@
+//! Contains a helper for capturing the scene around a point into a cubemap, e.g. for dynamic reflections.
+//!
+//! The scene is rendered six times, once per cubemap face, using the view matrices from [`math::cube_face_views()`].
+//! The resulting texture can then be sampled as a `samplerCube` by reflective materials.
+
+use super::framebuffer::*;
+use super::info::{GlCapability, GlInfo};
+use super::types::*;
+use crate::math::{self, Mat4, Vec3};
+
+use gl::types::*;
+
+/// Per-face information passed to the draw callback of [`CubemapCapture::capture()`].
+pub struct CubeFacePass<'a> {
+    pub face: GlCubeFace,
+    /// View matrix looking out through this face from the capture point.
+    pub view: &'a Mat4,
+    /// Projection matrix shared by all faces (90 degree field of view, square aspect ratio).
+    pub projection: &'a Mat4,
+}
+
+/// Manages a cubemap texture along with the framebuffer used to render into each of its faces.
+pub struct CubemapCapture {
+    size: GLsizei,
+    color: GlTexture,
+    // a single depth buffer can be reused for every face since each face is cleared before drawing,
+    // it is only held onto so it outlives the framebuffer attachment
+    _depth: GlTexture,
+    framebuffer: GlFramebuffer,
+}
+
+impl CubemapCapture {
+    /// Allocates a cubemap whose faces are each `size` by `size` pixels.
+    ///
+    /// This also enables seamless cubemap filtering (if supported), to avoid visible seams along face edges in reflections.
+    pub fn new(size: GLsizei) -> Result<Self, GlFramebufferError> {
+        if GlInfo::get().check(GlCapability::SeamlessCubeMap) {
+            unsafe {
+                gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+            }
+        }
+
+        let color = GlTexture::new_cube_map(gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE, size);
+        let depth = GlTexture::new_2d(
+            gl::DEPTH_COMPONENT24,
+            gl::DEPTH_COMPONENT,
+            gl::FLOAT,
+            size,
+            size,
+        );
+
+        let framebuffer = GlFramebuffer::new();
+        framebuffer.bind();
+        framebuffer.attach_cube_face(gl::COLOR_ATTACHMENT0, &color, GlCubeFace::PositiveX);
+        framebuffer.attach_texture_2d(gl::DEPTH_ATTACHMENT, &depth);
+        let status = framebuffer.check_status();
+        GlFramebuffer::unbind();
+        status?;
+
+        Ok(Self {
+            size,
+            color,
+            _depth: depth,
+            framebuffer,
+        })
+    }
+
+    /// The captured cubemap texture.
+    #[inline]
+    pub fn texture(&self) -> &GlTexture {
+        &self.color
+    }
+
+    /// Side length of each cubemap face, in pixels.
+    #[inline]
+    pub fn size(&self) -> GLsizei {
+        self.size
+    }
+
+    /// Renders the scene from `eye` into every face of the cubemap, calling `draw` once per face.
+    ///
+    /// Before each call the face is attached to the framebuffer and cleared, and depth testing is enabled.
+    /// `near` and `far` give the clipping planes of the per-face projection.
+    ///
+    /// The viewport is temporarily set to cover a whole face.
+    /// Afterwards the viewport, depth test, blend state and clear values are restored to what they were before the call,
+    /// and the default framebuffer is bound.
+    pub fn capture<F: FnMut(&CubeFacePass)>(&self, eye: Vec3, near: f32, far: f32, mut draw: F) {
+        let views = math::cube_face_views(eye);
+        let projection = math::cube_face_projection(near, far);
+
+        let saved = SavedState::save();
+        unsafe {
+            gl::Viewport(0, 0, self.size, self.size);
+            gl::Enable(gl::DEPTH_TEST);
+        }
+
+        self.framebuffer.bind();
+        for (face, view) in GlCubeFace::ALL.into_iter().zip(views.iter()) {
+            self.framebuffer
+                .attach_cube_face(gl::COLOR_ATTACHMENT0, &self.color, face);
+            unsafe {
+                gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+                gl::ClearDepth(1.0);
+                gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            }
+
+            draw(&CubeFacePass {
+                face,
+                view,
+                projection: &projection,
+            });
+        }
+
+        // cleanup
+        GlFramebuffer::unbind();
+        saved.restore();
+    }
+}
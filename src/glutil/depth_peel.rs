@@ -0,0 +1,357 @@
+//! Contains the framebuffer and render pass plumbing for order-independent transparency via depth peeling.
+//!
+//! Plain alpha blending is only correct when transparent geometry is drawn back to front,
+//! which cannot be guaranteed for intersecting or cyclically overlapping surfaces.
+//! Depth peeling instead renders the scene once per layer, where each pass only keeps the fragments
+//! lying strictly behind those kept by the previous pass, i.e. it "peels" one layer of depth at a time.
+//! The peeled layers are then blended back to front in a final compositing pass.
+//!
+//! User fragment shaders take part in peeling by having [`DEPTH_PEEL_GLSL`] spliced in
+//! (see [`inject_depth_peel_glsl()`]) and calling `peelDiscard()` before writing any output.
+
+use super::framebuffer::*;
+use super::types::*;
+use super::{GlProgram, GlShader};
+
+use gl::types::*;
+use thiserror::Error;
+use winit::dpi::PhysicalSize;
+
+/// The texture unit on which the previous layer's depth texture is bound during a peeling pass.
+///
+/// A high unit is used so it does not clash with textures bound by the user's own shaders.
+pub const DEPTH_PEEL_TEXTURE_UNIT: GLuint = 15;
+
+/// GLSL declarations needed by fragment shaders rendering into a [`DepthPeeler`] pass.
+///
+/// Defines the `peelDepth` and `peelFirstLayer` uniforms (set by [`PeelPass::set_uniforms()`]),
+/// along with the `peelDiscard()` function which discards any fragment that was already
+/// captured by an earlier layer.
+pub const DEPTH_PEEL_GLSL: &str = r#"
+uniform sampler2D peelDepth;
+uniform bool peelFirstLayer;
+
+void peelDiscard()
+{
+    if (!peelFirstLayer
+        && gl_FragCoord.z <= texelFetch(peelDepth, ivec2(gl_FragCoord.xy), 0).r)
+    {
+        discard;
+    }
+}
+"#;
+
+/// Splices [`DEPTH_PEEL_GLSL`] into a fragment shader source, right after its leading preprocessor directives
+/// (i.e. the `#version` directive and any `#extension` or other directives following it, before the first declaration).
+///
+/// Any blank lines or comments among those directives are kept in place.
+///
+/// Fails if the source has no `#version` directive, since the declarations need at least GLSL 1.30
+/// while such shaders are compiled as GLSL 1.10.
+pub fn inject_depth_peel_glsl(source: &str) -> Result<String, GlslVersionError> {
+    // directives such as #extension must precede every other token, so the declarations can only go after them
+    let mut has_version = false;
+    let mut insert_at = 0;
+    let mut offset = 0;
+    let mut in_comment = false;
+    for line in source.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim();
+        if in_comment {
+            in_comment = !line.contains("*/");
+        } else if line.starts_with('#') {
+            has_version |= line.starts_with("#version");
+            insert_at = offset;
+        } else if line.starts_with("/*") {
+            in_comment = !line.contains("*/");
+        } else if !line.is_empty() && !line.starts_with("//") {
+            break;
+        }
+    }
+    if !has_version {
+        return Err(GlslVersionError);
+    }
+
+    let (head, rest) = source.split_at(insert_at);
+    let newline = if head.ends_with('\n') { "" } else { "\n" };
+    Ok(format!("{}{}{}\n{}", head, newline, DEPTH_PEEL_GLSL, rest))
+}
+
+/// Error returned by [`inject_depth_peel_glsl()`] for shader sources lacking a `#version` directive.
+#[derive(Debug, Error)]
+#[error("shader source has no #version directive, depth peeling requires GLSL 1.30 or later")]
+pub struct GlslVersionError;
+
+const QUAD_VERT_SHADER: &str = include_str!("./shaders/fullscreen-quad.vert");
+const COMPOSITE_FRAG_SHADER: &str = include_str!("./shaders/peel-composite.frag");
+
+/// The render targets for a single peeled layer.
+struct PeelLayer {
+    framebuffer: GlFramebuffer,
+    color: GlTexture,
+    depth: GlTexture,
+}
+
+impl PeelLayer {
+    fn new(width: GLsizei, height: GLsizei) -> Result<Self, GlFramebufferError> {
+        let color = GlTexture::new_2d(gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE, width, height);
+        // a float depth buffer stores gl_FragCoord.z exactly, so fragments of the layer just peeled compare equal
+        // in peelDiscard() instead of rounding to a slightly smaller depth and being peeled a second time
+        let depth = GlTexture::new_2d(
+            gl::DEPTH_COMPONENT32F,
+            gl::DEPTH_COMPONENT,
+            gl::FLOAT,
+            width,
+            height,
+        );
+
+        let framebuffer = GlFramebuffer::new();
+        framebuffer.bind();
+        framebuffer.attach_texture_2d(gl::COLOR_ATTACHMENT0, &color);
+        framebuffer.attach_texture_2d(gl::DEPTH_ATTACHMENT, &depth);
+        let status = framebuffer.check_status();
+        GlFramebuffer::unbind();
+        status?;
+
+        Ok(Self {
+            framebuffer,
+            color,
+            depth,
+        })
+    }
+}
+
+/// Handle passed to the draw callback of [`DepthPeeler::peel()`] describing the current pass.
+pub struct PeelPass<'a> {
+    layer: usize,
+    prev_depth: Option<&'a GlTexture>,
+}
+
+impl PeelPass<'_> {
+    /// Index of the layer being peeled, starting from `0` for the front-most layer.
+    #[inline]
+    pub fn layer(&self) -> usize {
+        self.layer
+    }
+
+    /// Sets the uniforms declared by [`DEPTH_PEEL_GLSL`] on the given program.
+    ///
+    /// The program must currently be in use.
+    pub fn set_uniforms(&self, program: &GlProgram) {
+        unsafe {
+            let depth_location = gl::GetUniformLocation(program.handle(), c"peelDepth".as_ptr());
+            let first_location =
+                gl::GetUniformLocation(program.handle(), c"peelFirstLayer".as_ptr());
+            gl::Uniform1i(depth_location, DEPTH_PEEL_TEXTURE_UNIT as GLint);
+            gl::Uniform1i(first_location, self.prev_depth.is_none() as GLint);
+        }
+    }
+}
+
+/// Manages the per-layer framebuffers and the compositing pass for depth peeling.
+///
+/// A frame is rendered by first calling [`Self::peel()`] with a callback which draws the transparent geometry,
+/// followed by [`Self::composite()`] to blend the peeled layers onto the default framebuffer.
+pub struct DepthPeeler {
+    layers: Vec<PeelLayer>,
+    width: GLsizei,
+    height: GLsizei,
+    composite_program: GlProgram,
+    layer_color_location: GLint,
+    quad_vao: GLuint,
+}
+
+impl DepthPeeler {
+    /// Allocates `num_layers` peeling layers, each with render targets of the given size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_layers` is less than 2, since a single layer is equivalent to ordinary depth testing.
+    pub fn new(num_layers: usize, size: &PhysicalSize<u32>) -> Result<Self, GlFramebufferError> {
+        assert!(num_layers >= 2, "depth peeling requires at least 2 layers");
+
+        let (width, height) = (size.width as GLsizei, size.height as GLsizei);
+        let layers = (0..num_layers)
+            .map(|_| PeelLayer::new(width, height))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let shader_list = [
+            GlShader::compile_unwrap(GlShaderType::Vertex, QUAD_VERT_SHADER),
+            GlShader::compile_unwrap(GlShaderType::Fragment, COMPOSITE_FRAG_SHADER),
+        ];
+        let composite_program = GlProgram::link_unwrap(&shader_list);
+        let layer_color_location =
+            unsafe { gl::GetUniformLocation(composite_program.handle(), c"layerColor".as_ptr()) };
+
+        // the fullscreen quad is generated from gl_VertexID, but core profile still requires a bound VAO
+        let mut quad_vao = 0;
+        unsafe {
+            gl::GenVertexArrays(1, &mut quad_vao);
+        }
+
+        Ok(Self {
+            layers,
+            width,
+            height,
+            composite_program,
+            layer_color_location,
+            quad_vao,
+        })
+    }
+
+    /// Number of layers peeled per frame.
+    #[inline]
+    pub fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Re-allocates the render targets to match the new size, e.g. from [`GlAppDelegate::reshape`](crate::app::GlAppDelegate::reshape).
+    pub fn resize(&mut self, size: &PhysicalSize<u32>) -> Result<(), GlFramebufferError> {
+        let (width, height) = (size.width as GLsizei, size.height as GLsizei);
+        self.layers = (0..self.layers.len())
+            .map(|_| PeelLayer::new(width, height))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.width = width;
+        self.height = height;
+        Ok(())
+    }
+
+    /// Runs one pass per layer, calling `draw` to render the transparent geometry into each.
+    ///
+    /// Before each call the layer's framebuffer is bound and cleared, the viewport covers the whole layer,
+    /// depth testing is enabled and blending is disabled.
+    /// The callback should use a program built with [`DEPTH_PEEL_GLSL`] and call [`PeelPass::set_uniforms()`] on it.
+    ///
+    /// Afterwards the viewport, depth test, blend state and clear values are restored to what they were before the call,
+    /// the default framebuffer is bound and texture unit `0` is active.
+    pub fn peel<F: FnMut(&PeelPass)>(&self, mut draw: F) {
+        let saved = SavedState::save();
+        unsafe {
+            gl::Viewport(0, 0, self.width, self.height);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::Disable(gl::BLEND);
+        }
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            let prev_depth = i.checked_sub(1).map(|prev| &self.layers[prev].depth);
+            layer.framebuffer.bind();
+            unsafe {
+                gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+                gl::ClearDepth(1.0);
+                gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+                // expose the previous layer's depth for the comparison in peelDiscard()
+                match prev_depth {
+                    Some(depth) => depth.bind_to_unit(DEPTH_PEEL_TEXTURE_UNIT),
+                    None => {
+                        gl::ActiveTexture(gl::TEXTURE0 + DEPTH_PEEL_TEXTURE_UNIT);
+                        gl::BindTexture(gl::TEXTURE_2D, 0);
+                    }
+                }
+                gl::ActiveTexture(gl::TEXTURE0);
+            }
+
+            draw(&PeelPass {
+                layer: i,
+                prev_depth,
+            });
+        }
+
+        // cleanup
+        GlFramebuffer::unbind();
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + DEPTH_PEEL_TEXTURE_UNIT);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+        saved.restore();
+    }
+
+    /// Blends the peeled layers back to front onto the currently bound viewport of the default framebuffer.
+    ///
+    /// Afterwards the viewport, depth test, blend state and clear values are restored to what they were before the call,
+    /// while the default framebuffer, program `0` and vertex array `0` are left bound
+    /// and nothing is bound to `GL_TEXTURE_2D` on texture unit `0`.
+    pub fn composite(&self) {
+        let saved = SavedState::save();
+        GlFramebuffer::unbind();
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            gl::UseProgram(self.composite_program.handle());
+            gl::Uniform1i(self.layer_color_location, 0);
+            gl::BindVertexArray(self.quad_vao);
+        }
+
+        for layer in self.layers.iter().rev() {
+            layer.color.bind_to_unit(0);
+            unsafe {
+                gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+            }
+        }
+
+        // cleanup
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::BindVertexArray(0);
+            gl::UseProgram(0);
+        }
+        saved.restore();
+    }
+}
+
+impl Drop for DepthPeeler {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.quad_vao);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inject_after_version_on_first_line() {
+        let source = "#version 330\nout vec4 outputColor;\n";
+        let injected = inject_depth_peel_glsl(source).unwrap();
+        assert_eq!(
+            injected,
+            format!("#version 330\n{}\nout vec4 outputColor;\n", DEPTH_PEEL_GLSL)
+        );
+    }
+
+    #[test]
+    fn inject_skips_leading_blank_lines_and_comments() {
+        let source = "\n// fragment shader\n  #version 330\nvoid main() {}";
+        let injected = inject_depth_peel_glsl(source).unwrap();
+        assert!(injected.starts_with("\n// fragment shader\n  #version 330\n"));
+        assert!(injected.ends_with(&format!("{}\nvoid main() {{}}", DEPTH_PEEL_GLSL)));
+    }
+
+    #[test]
+    fn inject_after_extension_directives() {
+        let head = "#version 330\n/* needed for\n   layout(binding) */\n#extension GL_ARB_shading_language_420pack : require\n\n// more\n#extension GL_ARB_separate_shader_objects : enable\n";
+        let body = "out vec4 outputColor;\n#define UNUSED 1\n";
+        let injected = inject_depth_peel_glsl(&format!("{}{}", head, body)).unwrap();
+        assert_eq!(injected, format!("{}{}\n{}", head, DEPTH_PEEL_GLSL, body));
+    }
+
+    #[test]
+    fn inject_into_version_only_source() {
+        let injected = inject_depth_peel_glsl("#version 330").unwrap();
+        assert_eq!(injected, format!("#version 330\n{}\n", DEPTH_PEEL_GLSL));
+    }
+
+    #[test]
+    fn inject_without_version_fails() {
+        assert!(inject_depth_peel_glsl("void main() {}\n").is_err());
+        assert!(
+            inject_depth_peel_glsl("#extension GL_ARB_gpu_shader5 : enable\nvoid main() {}\n")
+                .is_err()
+        );
+    }
+}
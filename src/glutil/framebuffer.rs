@@ -0,0 +1,335 @@
+//! Contains wrappers and methods for handling OpenGL texture and framebuffer objects.
+
+use super::info::{GlCapability, GlInfo};
+use super::types::*;
+
+use gl::types::*;
+use thiserror::Error;
+
+/// An RAII struct managing the lifetime of a texture object.
+///
+/// It represents a uniquely owned texture, hence is not [`Copy`] or [`Clone`].
+#[derive(Debug)]
+pub struct GlTexture {
+    id: GLuint,
+    target: GlTextureTarget,
+}
+
+impl GlTexture {
+    /// Generates a new texture object which will be bound to the provided target.
+    ///
+    /// No storage is allocated; the caller is expected to bind the texture and specify its image(s).
+    pub fn new(target: GlTextureTarget) -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenTextures(1, &mut id);
+        }
+        Self { id, target }
+    }
+
+    /// Creates a 2D texture with allocated (but uninitialized) storage of the given size and format.
+    ///
+    /// The texture uses nearest filtering and clamps to the edge,
+    /// which is what is usually wanted for textures used as render targets.
+    pub fn new_2d(
+        internal_format: GLenum,
+        format: GLenum,
+        data_type: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) -> Self {
+        let result = Self::new(GlTextureTarget::Texture2D);
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, result.id);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                internal_format as GLint,
+                width,
+                height,
+                0,
+                format,
+                data_type,
+                std::ptr::null(),
+            );
+            set_render_target_params(gl::TEXTURE_2D);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        result
+    }
+
+    /// Creates a cubemap texture with allocated (but uninitialized) storage for all six square faces.
+    ///
+    /// The texture uses linear filtering and clamps to the edge on all axes,
+    /// which is suitable for sampling it as an environment map.
+    pub fn new_cube_map(
+        internal_format: GLenum,
+        format: GLenum,
+        data_type: GLenum,
+        size: GLsizei,
+    ) -> Self {
+        let result = Self::new(GlTextureTarget::CubeMap);
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, result.id);
+            for face in GlCubeFace::ALL {
+                gl::TexImage2D(
+                    face.value(),
+                    0,
+                    internal_format as GLint,
+                    size,
+                    size,
+                    0,
+                    format,
+                    data_type,
+                    std::ptr::null(),
+                );
+            }
+            let target = gl::TEXTURE_CUBE_MAP;
+            gl::TexParameteri(target, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
+            gl::TexParameteri(target, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+            gl::TexParameteri(target, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+            gl::TexParameteri(target, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+            gl::TexParameteri(target, gl::TEXTURE_WRAP_R, gl::CLAMP_TO_EDGE as GLint);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+        }
+        result
+    }
+
+    /// Get the `GLuint` this struct is wrapping.
+    #[inline]
+    pub fn handle(&self) -> GLuint {
+        self.id
+    }
+
+    /// Get the target this texture is bound to.
+    #[inline]
+    pub fn target(&self) -> GlTextureTarget {
+        self.target
+    }
+
+    /// Sets the maximum degree of anisotropic filtering used when sampling this texture,
+    /// clamped to what the driver supports.
+    ///
+    /// Does nothing (besides logging a warning) if [`GlCapability::AnisotropicFiltering`] is unsupported.
+    pub fn set_max_anisotropy(&self, amount: GLfloat) {
+        let info = GlInfo::get();
+        if !info.check(GlCapability::AnisotropicFiltering) {
+            return;
+        }
+
+        let target = self.target.value();
+        unsafe {
+            gl::BindTexture(target, self.id);
+            gl::TexParameterf(
+                target,
+                gl::TEXTURE_MAX_ANISOTROPY_EXT,
+                amount.clamp(1.0, info.max_anisotropy),
+            );
+            gl::BindTexture(target, 0);
+        }
+    }
+
+    /// Binds this texture to its target on the given texture unit (e.g. `0` for `GL_TEXTURE0`).
+    ///
+    /// Note that this leaves the active texture unit set to `unit`.
+    pub fn bind_to_unit(&self, unit: GLuint) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::BindTexture(self.target.value(), self.id);
+        }
+    }
+}
+
+impl Drop for GlTexture {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteTextures(1, &self.id);
+        }
+    }
+}
+
+// Render targets are sampled 1:1 with the framebuffer, so filtering/wrapping should never kick in
+unsafe fn set_render_target_params(target: GLenum) {
+    gl::TexParameteri(target, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+    gl::TexParameteri(target, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+    gl::TexParameteri(target, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+    gl::TexParameteri(target, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+}
+
+/// An RAII struct managing the lifetime of a framebuffer object.
+///
+/// It represents a uniquely owned framebuffer, hence is not [`Copy`] or [`Clone`].
+#[derive(Debug)]
+pub struct GlFramebuffer {
+    id: GLuint,
+}
+
+impl GlFramebuffer {
+    /// Generates a new framebuffer object with no attachments.
+    pub fn new() -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenFramebuffers(1, &mut id);
+        }
+        Self { id }
+    }
+
+    /// Get the `GLuint` this struct is wrapping.
+    #[inline]
+    pub fn handle(&self) -> GLuint {
+        self.id
+    }
+
+    /// Binds this framebuffer to `GL_FRAMEBUFFER` (i.e. for both drawing and reading).
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+        }
+    }
+
+    /// Restores the default (window-system provided) framebuffer.
+    pub fn unbind() {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    /// Attaches a 2D texture to the given attachment point (e.g. `GL_COLOR_ATTACHMENT0`).
+    ///
+    /// The framebuffer must currently be bound.
+    pub fn attach_texture_2d(&self, attachment: GLenum, texture: &GlTexture) {
+        unsafe {
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                attachment,
+                gl::TEXTURE_2D,
+                texture.handle(),
+                0,
+            );
+        }
+    }
+
+    /// Attaches a single face of a cubemap texture to the given attachment point.
+    ///
+    /// The framebuffer must currently be bound.
+    pub fn attach_cube_face(&self, attachment: GLenum, texture: &GlTexture, face: GlCubeFace) {
+        unsafe {
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                attachment,
+                face.value(),
+                texture.handle(),
+                0,
+            );
+        }
+    }
+
+    /// Checks the completeness of the framebuffer with `glCheckFramebufferStatus`.
+    ///
+    /// The framebuffer must currently be bound.
+    pub fn check_status(&self) -> Result<(), GlFramebufferError> {
+        let status = unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) };
+        if status == gl::FRAMEBUFFER_COMPLETE {
+            Ok(())
+        } else {
+            Err(GlFramebufferError { status })
+        }
+    }
+}
+
+impl Default for GlFramebuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for GlFramebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.id);
+        }
+    }
+}
+
+/// Snapshot of the global state changed by the render pass helpers (e.g. [`DepthPeeler`](super::depth_peel::DepthPeeler)
+/// and [`CubemapCapture`](super::cubemap::CubemapCapture)), so they can restore it afterwards:
+/// the viewport, depth test, blending and blend function, and the clear color and depth.
+pub(crate) struct SavedState {
+    viewport: [GLint; 4],
+    depth_test: bool,
+    blend: bool,
+    /// `[src_rgb, dst_rgb, src_alpha, dst_alpha]`, as passed to `glBlendFuncSeparate`.
+    blend_func: [GLint; 4],
+    clear_color: [GLfloat; 4],
+    clear_depth: GLdouble,
+}
+
+impl SavedState {
+    pub(crate) fn save() -> Self {
+        let mut viewport = [0; 4];
+        let mut blend_func = [0; 4];
+        let mut clear_color = [0.0; 4];
+        let mut clear_depth = 0.0;
+        unsafe {
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+            gl::GetIntegerv(gl::BLEND_SRC_RGB, &mut blend_func[0]);
+            gl::GetIntegerv(gl::BLEND_DST_RGB, &mut blend_func[1]);
+            gl::GetIntegerv(gl::BLEND_SRC_ALPHA, &mut blend_func[2]);
+            gl::GetIntegerv(gl::BLEND_DST_ALPHA, &mut blend_func[3]);
+            gl::GetFloatv(gl::COLOR_CLEAR_VALUE, clear_color.as_mut_ptr());
+            gl::GetDoublev(gl::DEPTH_CLEAR_VALUE, &mut clear_depth);
+            Self {
+                viewport,
+                depth_test: gl::IsEnabled(gl::DEPTH_TEST) == gl::TRUE,
+                blend: gl::IsEnabled(gl::BLEND) == gl::TRUE,
+                blend_func,
+                clear_color,
+                clear_depth,
+            }
+        }
+    }
+
+    pub(crate) fn restore(&self) {
+        let [x, y, width, height] = self.viewport;
+        let [src_rgb, dst_rgb, src_alpha, dst_alpha] = self.blend_func.map(|f| f as GLenum);
+        let [red, green, blue, alpha] = self.clear_color;
+        unsafe {
+            gl::Viewport(x, y, width, height);
+            set_enabled(gl::DEPTH_TEST, self.depth_test);
+            set_enabled(gl::BLEND, self.blend);
+            gl::BlendFuncSeparate(src_rgb, dst_rgb, src_alpha, dst_alpha);
+            gl::ClearColor(red, green, blue, alpha);
+            gl::ClearDepth(self.clear_depth);
+        }
+    }
+}
+
+unsafe fn set_enabled(capability: GLenum, enabled: bool) {
+    if enabled {
+        gl::Enable(capability);
+    } else {
+        gl::Disable(capability);
+    }
+}
+
+/// Represents an incomplete [`GlFramebuffer`], as reported by `glCheckFramebufferStatus`.
+#[derive(Debug, Error)]
+#[error("framebuffer incomplete: {}", get_framebuffer_status(*.status))]
+pub struct GlFramebufferError {
+    status: GLenum,
+}
+
+#[inline]
+const fn get_framebuffer_status(status: GLenum) -> &'static str {
+    match status {
+        gl::FRAMEBUFFER_UNDEFINED => "undefined",
+        gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => "incomplete attachment",
+        gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => "missing attachment",
+        gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => "incomplete draw buffer",
+        gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => "incomplete read buffer",
+        gl::FRAMEBUFFER_UNSUPPORTED => "unsupported",
+        gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => "incomplete multisample",
+        gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => "incomplete layer targets",
+        _ => "unknown",
+    }
+}
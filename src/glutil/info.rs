@@ -0,0 +1,308 @@
+//! Contains a registry of the OpenGL implementation's version, extensions and optional capabilities.
+//!
+//! Wrappers consult [`GlInfo::check()`] before using an optional feature,
+//! so that it is skipped with a warning on drivers lacking it instead of generating OpenGL errors.
+
+use std::collections::HashSet;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use gl::types::*;
+
+/// Optional features which are not guaranteed by the OpenGL 4.1 core profile on every driver.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GlCapability {
+    /// Anisotropic texture filtering (`GL_EXT_texture_filter_anisotropic`).
+    AnisotropicFiltering,
+    /// Debug message callbacks (`GL_KHR_debug`, core since OpenGL 4.3).
+    DebugOutput,
+    /// Filtering across cubemap face edges (`GL_ARB_seamless_cube_map`, core since OpenGL 3.2).
+    SeamlessCubeMap,
+}
+
+impl GlCapability {
+    #[inline]
+    const fn name(&self) -> &'static str {
+        match self {
+            GlCapability::AnisotropicFiltering => "anisotropic filtering",
+            GlCapability::DebugOutput => "debug output",
+            GlCapability::SeamlessCubeMap => "seamless cubemap filtering",
+        }
+    }
+}
+
+/// Information about the OpenGL implementation backing the current context.
+#[derive(Debug)]
+pub struct GlInfo {
+    pub vendor: String,
+    pub renderer: String,
+    /// The `(major, minor)` version of the context.
+    pub version: (GLint, GLint),
+    pub glsl_version: String,
+    /// Maximum degree of anisotropy supported, `1.0` if anisotropic filtering is unsupported.
+    pub max_anisotropy: GLfloat,
+    extensions: HashSet<String>,
+    supported: PerCapability<bool>,
+    warned: PerCapability<AtomicBool>,
+}
+
+/// Holds one value for each [`GlCapability`].
+#[derive(Debug, Default, PartialEq)]
+struct PerCapability<T> {
+    anisotropic_filtering: T,
+    debug_output: T,
+    seamless_cube_map: T,
+}
+
+impl<T> PerCapability<T> {
+    #[inline]
+    fn get(&self, capability: GlCapability) -> &T {
+        match capability {
+            GlCapability::AnisotropicFiltering => &self.anisotropic_filtering,
+            GlCapability::DebugOutput => &self.debug_output,
+            GlCapability::SeamlessCubeMap => &self.seamless_cube_map,
+        }
+    }
+}
+
+static GL_INFO: OnceLock<GlInfo> = OnceLock::new();
+
+impl GlInfo {
+    /// Get the information for the OpenGL implementation, querying it the first time this is called.
+    ///
+    /// Must not be called before a context has been made current and the OpenGL functions have been loaded.
+    /// The information is queried only once, so it assumes all contexts created by the application share the same driver.
+    pub fn get() -> &'static GlInfo {
+        GL_INFO.get_or_init(Self::query)
+    }
+
+    /// Queries the OpenGL implementation backing the current context.
+    pub fn query() -> Self {
+        let mut version = (0, 0);
+        let mut num_extensions = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAJOR_VERSION, &mut version.0);
+            gl::GetIntegerv(gl::MINOR_VERSION, &mut version.1);
+            gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut num_extensions);
+        }
+
+        let extensions = collect_extensions(
+            (0..num_extensions as GLuint)
+                .map(|i| unsafe { ptr_to_string(gl::GetStringi(gl::EXTENSIONS, i)) }),
+        );
+        let supported =
+            detect_capabilities(version, &extensions, gl::DebugMessageCallback::is_loaded());
+
+        let mut max_anisotropy = 1.0;
+        if supported.anisotropic_filtering {
+            unsafe {
+                gl::GetFloatv(gl::MAX_TEXTURE_MAX_ANISOTROPY_EXT, &mut max_anisotropy);
+            }
+        }
+
+        let (vendor, renderer, glsl_version) = unsafe {
+            (
+                ptr_to_string(gl::GetString(gl::VENDOR)),
+                ptr_to_string(gl::GetString(gl::RENDERER)),
+                ptr_to_string(gl::GetString(gl::SHADING_LANGUAGE_VERSION)),
+            )
+        };
+
+        Self {
+            vendor,
+            renderer,
+            version,
+            glsl_version,
+            max_anisotropy,
+            extensions,
+            supported,
+            warned: Default::default(),
+        }
+    }
+
+    /// Whether the implementation advertises the named extension (e.g. `"GL_EXT_texture_filter_anisotropic"`).
+    pub fn has_extension(&self, name: &str) -> bool {
+        self.extensions.contains(name)
+    }
+
+    /// Iterates over the names of all extensions advertised by the implementation, in no particular order.
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.extensions.iter().map(String::as_str)
+    }
+
+    /// Whether the given optional capability is available.
+    #[inline]
+    pub fn supports(&self, capability: GlCapability) -> bool {
+        *self.supported.get(capability)
+    }
+
+    /// Like [`Self::supports()`], but logs a warning (once per capability) if it is unavailable.
+    ///
+    /// Intended for wrappers which skip an optional feature rather than failing.
+    pub fn check(&self, capability: GlCapability) -> bool {
+        let supported = self.supports(capability);
+        if !supported && !self.warned.get(capability).swap(true, Ordering::Relaxed) {
+            eprintln!(
+                "warning: {} is not supported by this driver ({}), skipping it",
+                capability.name(),
+                self.renderer
+            );
+        }
+        supported
+    }
+}
+
+/// Builds the set of extension names, skipping empty names (i.e. those which failed to be queried).
+fn collect_extensions(names: impl IntoIterator<Item = String>) -> HashSet<String> {
+    names.into_iter().filter(|name| !name.is_empty()).collect()
+}
+
+/// Determines which optional capabilities are available from the context version and advertised extensions.
+///
+/// `debug_callback_loaded` is whether `glDebugMessageCallback` was loaded,
+/// since `GL_KHR_debug` only enables the unsuffixed entry points and they may still be missing.
+fn detect_capabilities(
+    version: (GLint, GLint),
+    extensions: &HashSet<String>,
+    debug_callback_loaded: bool,
+) -> PerCapability<bool> {
+    let has_extension = |name: &str| extensions.contains(name);
+    PerCapability {
+        anisotropic_filtering: has_extension("GL_EXT_texture_filter_anisotropic")
+            || has_extension("GL_ARB_texture_filter_anisotropic"),
+        debug_output: (version >= (4, 3) || has_extension("GL_KHR_debug")) && debug_callback_loaded,
+        seamless_cube_map: version >= (3, 2) || has_extension("GL_ARB_seamless_cube_map"),
+    }
+}
+
+/// Copies a string returned by `glGetString`/`glGetStringi`, returning an empty string for null pointers.
+unsafe fn ptr_to_string(ptr: *const GLubyte) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    CStr::from_ptr(ptr as *const GLchar)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Enables `GL_KHR_debug` output, printing every message reported by the driver to stderr.
+///
+/// Does nothing (besides logging a warning) if [`GlCapability::DebugOutput`] is unsupported, e.g. on MacOS.
+pub fn enable_debug_output() {
+    if !GlInfo::get().check(GlCapability::DebugOutput) {
+        return;
+    }
+
+    unsafe {
+        gl::Enable(gl::DEBUG_OUTPUT);
+        // report messages from within the offending call, so they line up with any other logging
+        gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+        gl::DebugMessageCallback(Some(print_debug_message), std::ptr::null());
+    }
+}
+
+extern "system" fn print_debug_message(
+    _source: GLenum,
+    _gltype: GLenum,
+    id: GLuint,
+    severity: GLenum,
+    length: GLsizei,
+    message: *const GLchar,
+    _user_param: *mut std::ffi::c_void,
+) {
+    // SAFETY: the driver guarantees `message` points to `length` valid characters
+    let message = unsafe { std::slice::from_raw_parts(message as *const u8, length as usize) };
+    eprintln!(
+        "GL debug ({}, id {}): {}",
+        get_debug_severity(severity),
+        id,
+        String::from_utf8_lossy(message)
+    );
+}
+
+#[inline]
+const fn get_debug_severity(severity: GLenum) -> &'static str {
+    match severity {
+        gl::DEBUG_SEVERITY_HIGH => "high",
+        gl::DEBUG_SEVERITY_MEDIUM => "medium",
+        gl::DEBUG_SEVERITY_LOW => "low",
+        gl::DEBUG_SEVERITY_NOTIFICATION => "notification",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extensions(names: &[&str]) -> HashSet<String> {
+        collect_extensions(names.iter().map(|name| name.to_string()))
+    }
+
+    #[test]
+    fn collect_extensions_skips_empty_names() {
+        let set = extensions(&["GL_KHR_debug", "", "GL_KHR_debug"]);
+        assert_eq!(set, HashSet::from([String::from("GL_KHR_debug")]));
+    }
+
+    #[test]
+    fn capabilities_from_core_version() {
+        let core_41 = detect_capabilities((4, 1), &extensions(&[]), true);
+        assert_eq!(
+            core_41,
+            PerCapability {
+                anisotropic_filtering: false,
+                debug_output: false,
+                seamless_cube_map: true,
+            }
+        );
+
+        let core_43 = detect_capabilities((4, 3), &extensions(&[]), true);
+        assert!(core_43.debug_output);
+        assert!(!core_43.anisotropic_filtering);
+    }
+
+    #[test]
+    fn capabilities_from_extensions() {
+        for name in [
+            "GL_EXT_texture_filter_anisotropic",
+            "GL_ARB_texture_filter_anisotropic",
+        ] {
+            assert!(detect_capabilities((4, 1), &extensions(&[name]), false).anisotropic_filtering);
+        }
+        assert!(detect_capabilities((4, 1), &extensions(&["GL_KHR_debug"]), true).debug_output);
+        assert!(
+            detect_capabilities((3, 1), &extensions(&["GL_ARB_seamless_cube_map"]), false)
+                .seamless_cube_map
+        );
+        assert!(!detect_capabilities((3, 1), &extensions(&[]), false).seamless_cube_map);
+    }
+
+    #[test]
+    fn debug_output_requires_loaded_entry_points() {
+        assert!(!detect_capabilities((4, 1), &extensions(&["GL_KHR_debug"]), false).debug_output);
+        assert!(!detect_capabilities((4, 6), &extensions(&[]), false).debug_output);
+    }
+
+    #[test]
+    fn check_warns_once_per_capability() {
+        let info = GlInfo {
+            vendor: String::new(),
+            renderer: String::from("test"),
+            version: (4, 1),
+            glsl_version: String::new(),
+            max_anisotropy: 1.0,
+            extensions: extensions(&[]),
+            supported: detect_capabilities((4, 1), &extensions(&[]), false),
+            warned: Default::default(),
+        };
+
+        assert!(info.check(GlCapability::SeamlessCubeMap));
+        assert!(!info.warned.seamless_cube_map.load(Ordering::Relaxed));
+
+        assert!(!info.check(GlCapability::DebugOutput));
+        assert!(info.warned.debug_output.load(Ordering::Relaxed));
+        assert!(!info.warned.anisotropic_filtering.load(Ordering::Relaxed));
+    }
+}
@@ -3,6 +3,7 @@
 use super::types::*;
 
 use std::ffi;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 use gl::types::*;
 use thiserror::Error;
@@ -92,11 +93,20 @@ impl Drop for GlShader {
     }
 }
 
+const FALLBACK_VERT_SHADER: &str = include_str!("./shaders/fallback.vert");
+const FALLBACK_FRAG_SHADER: &str = include_str!("./shaders/fallback.frag");
+
+/// Handle of the fallback program shared within the current context, `0` if it has not been compiled yet.
+static FALLBACK_PROGRAM: AtomicU32 = AtomicU32::new(0);
+/// Set whenever [`GlProgram::build_or_fallback()`] substitutes the fallback program.
+static FALLBACK_SUBSTITUTED: AtomicBool = AtomicBool::new(false);
+
 /// An RAII struct managing the lifetime of a program object.
 ///
-/// It represents a uniquely owned program, hence is not [`Copy`] or [`Clone`].
+/// It represents a uniquely owned program (except for [`GlProgram::fallback()`]), hence is not [`Copy`] or [`Clone`].
 pub struct GlProgram {
     id: GLuint,
+    is_fallback: bool,
 }
 
 impl GlProgram {
@@ -105,7 +115,10 @@ impl GlProgram {
         unsafe {
             let program = gl::CreateProgram();
             // Wrap program now so it is dropped if failure occurs later in the method
-            let result = Self { id: program };
+            let result = Self {
+                id: program,
+                is_fallback: false,
+            };
 
             shaders
                 .iter()
@@ -137,6 +150,83 @@ impl GlProgram {
             .unwrap()
     }
 
+    /// Compiles each of the provided GLSL sources into a shader and links them into a program.
+    pub fn build(sources: &[(GlShaderType, &str)]) -> Result<Self, GlProgramError> {
+        let shaders = sources
+            .iter()
+            .map(|&(shader_type, source)| GlShader::compile(shader_type, source))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::link(&shaders)?)
+    }
+
+    /// Like [`Self::build()`] but substitutes [`Self::fallback()`] on failure, after printing the error message.
+    ///
+    /// This keeps the scene rendering (in an obviously wrong color) while the GLSL is being fixed.
+    /// Substitutions are recorded, so automated tests can still detect them with [`Self::take_fallback_substituted()`].
+    pub fn build_or_fallback(sources: &[(GlShaderType, &str)]) -> Self {
+        Self::build(sources).unwrap_or_else(|e| {
+            eprintln!("failed to build program, substituting fallback: {}", e);
+            FALLBACK_SUBSTITUTED.store(true, Ordering::Relaxed);
+            Self::fallback()
+        })
+    }
+
+    /// Get the built-in "error material" program, which renders a magenta/black checkerboard.
+    ///
+    /// It expects vertex positions in clip space on attribute location `0`, like most of the examples,
+    /// and declares no uniforms (so setting uniforms meant for the original program is silently ignored).
+    ///
+    /// The program is compiled once per context by [`Self::compile_fallback()`], which the window initialization
+    /// functions call right after creating the context (or lazily on first use if the context was created elsewhere).
+    /// Every returned value shares that program, which is deleted along with the context rather than when dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the built-in shaders fail to compile or link, which indicates a driver problem.
+    pub fn fallback() -> Self {
+        let mut id = FALLBACK_PROGRAM.load(Ordering::Relaxed);
+        if id == 0 {
+            id = Self::compile_fallback();
+        }
+        Self {
+            id,
+            is_fallback: true,
+        }
+    }
+
+    /// Compiles the program shared by [`Self::fallback()`] for the current context, returning its handle.
+    ///
+    /// This must be called again whenever a new context is made current,
+    /// since the previously shared program belongs to the old context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the built-in shaders fail to compile or link, which indicates a driver problem.
+    pub fn compile_fallback() -> GLuint {
+        let shaders = [
+            GlShader::compile_unwrap(GlShaderType::Vertex, FALLBACK_VERT_SHADER),
+            GlShader::compile_unwrap(GlShaderType::Fragment, FALLBACK_FRAG_SHADER),
+        ];
+        // the program is kept alive for as long as the context, so never drop the wrapper
+        let id = std::mem::ManuallyDrop::new(Self::link_unwrap(&shaders)).id;
+        FALLBACK_PROGRAM.store(id, Ordering::Relaxed);
+        id
+    }
+
+    /// Whether this is the program shared by [`Self::fallback()`], e.g. to flag it in an overlay.
+    #[inline]
+    pub fn is_fallback(&self) -> bool {
+        self.is_fallback
+    }
+
+    /// Whether [`Self::build_or_fallback()`] has substituted the fallback program since the last call,
+    /// resetting the flag.
+    ///
+    /// Intended for automated tests, which should fail rather than pass while rendering the fallback.
+    pub fn take_fallback_substituted() -> bool {
+        FALLBACK_SUBSTITUTED.swap(false, Ordering::Relaxed)
+    }
+
     /// Get the `GLuint` this struct is wrapping.
     #[inline]
     pub fn handle(&self) -> GLuint {
@@ -174,6 +264,10 @@ impl GlProgram {
 
 impl Drop for GlProgram {
     fn drop(&mut self) {
+        // the fallback program is shared, so it is left to be deleted along with the context
+        if self.is_fallback {
+            return;
+        }
         unsafe {
             gl::DeleteProgram(self.id);
         }
@@ -201,6 +295,15 @@ pub struct GlShaderCompileError {
     msg: ffi::CString,
 }
 
+/// Errors that can occur when building a [`GlProgram`] from GLSL sources.
+#[derive(Debug, Error)]
+pub enum GlProgramError {
+    #[error(transparent)]
+    ShaderError(#[from] GlShaderError),
+    #[error(transparent)]
+    LinkError(#[from] GlProgramLinkError),
+}
+
 /// Represents an OpenGL linker error when linking a [`GlProgram`].
 #[derive(Debug, Error)]
 #[error(
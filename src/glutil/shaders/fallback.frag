@@ -0,0 +1,13 @@
+#version 330
+
+// Magenta/black checkerboard in screen space, which is hard to mistake for intentional output
+const float CHECKER_SIZE = 16.0f;
+const vec4 magenta = vec4(1.0f, 0.0f, 1.0f, 1.0f);
+const vec4 black = vec4(0.0f, 0.0f, 0.0f, 1.0f);
+
+out vec4 outputColor;
+void main()
+{
+    ivec2 cell = ivec2(floor(gl_FragCoord.xy / CHECKER_SIZE));
+    outputColor = ((cell.x + cell.y) % 2 == 0) ? magenta : black;
+}
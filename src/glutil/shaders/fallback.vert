@@ -0,0 +1,7 @@
+#version 330
+
+layout(location = 0) in vec4 position;
+void main()
+{
+    gl_Position = position;
+}
@@ -0,0 +1,12 @@
+#version 330
+
+// Generates a screen-filling triangle strip from gl_VertexID, so no vertex buffer is needed.
+// Draw with glDrawArrays(GL_TRIANGLE_STRIP, 0, 4).
+smooth out vec2 texCoord;
+
+void main()
+{
+    vec2 pos = vec2(float(gl_VertexID & 1), float(gl_VertexID >> 1));
+    texCoord = pos;
+    gl_Position = vec4(pos * 2.0f - 1.0f, 0.0f, 1.0f);
+}
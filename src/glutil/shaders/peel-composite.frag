@@ -0,0 +1,11 @@
+#version 330
+
+smooth in vec2 texCoord;
+uniform sampler2D layerColor;
+
+out vec4 outputColor;
+
+void main()
+{
+    outputColor = texture(layerColor, texCoord);
+}
@@ -39,3 +39,55 @@ impl GlBufUsage {
         }
     }
 }
+
+/// Type-safe wrapper over `GLenum` which can only represent valid texture targets.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GlTextureTarget {
+    Texture2D,
+    CubeMap,
+}
+
+impl GlTextureTarget {
+    /// Convert to the underlying `GLenum` value.
+    pub const fn value(&self) -> GLenum {
+        match self {
+            GlTextureTarget::Texture2D => gl::TEXTURE_2D,
+            GlTextureTarget::CubeMap => gl::TEXTURE_CUBE_MAP,
+        }
+    }
+}
+
+/// Type-safe wrapper over `GLenum` which can only represent valid cubemap face targets.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GlCubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl GlCubeFace {
+    /// All faces, in the same order as their `GLenum` values.
+    pub const ALL: [GlCubeFace; 6] = [
+        GlCubeFace::PositiveX,
+        GlCubeFace::NegativeX,
+        GlCubeFace::PositiveY,
+        GlCubeFace::NegativeY,
+        GlCubeFace::PositiveZ,
+        GlCubeFace::NegativeZ,
+    ];
+
+    /// Convert to the underlying `GLenum` value.
+    pub const fn value(&self) -> GLenum {
+        match self {
+            GlCubeFace::PositiveX => gl::TEXTURE_CUBE_MAP_POSITIVE_X,
+            GlCubeFace::NegativeX => gl::TEXTURE_CUBE_MAP_NEGATIVE_X,
+            GlCubeFace::PositiveY => gl::TEXTURE_CUBE_MAP_POSITIVE_Y,
+            GlCubeFace::NegativeY => gl::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+            GlCubeFace::PositiveZ => gl::TEXTURE_CUBE_MAP_POSITIVE_Z,
+            GlCubeFace::NegativeZ => gl::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+        }
+    }
+}
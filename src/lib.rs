@@ -1,5 +1,6 @@
 //! Contains various re-usable functionalities employed throughout the different examples.
 
+use std::panic::{self, AssertUnwindSafe};
 use std::{ffi::CString, num::NonZeroU32};
 
 use anyhow::{anyhow, Context};
@@ -8,13 +9,110 @@ use glutin::{display::GetGlDisplay, surface};
 use winit::{dpi, event_loop, raw_window_handle::HasWindowHandle, window};
 
 pub mod app;
+pub mod console;
 pub mod glutil;
+pub mod math;
+pub mod scene;
 
-// Hard-coded constants for simplicity
+// Default window dimensions
 const WIDTH: NonZeroU32 = unsafe { NonZeroU32::new_unchecked(800) };
 const HEIGHT: NonZeroU32 = unsafe { NonZeroU32::new_unchecked(800) };
 
-/// Initializes a window with an OpenGL context.
+/// Options for the window created by [`init_window_and_context_with()`].
+///
+/// Constructed with [`WindowConfig::new()`] and customized using the `with_*` methods.
+#[derive(Clone, Debug)]
+pub struct WindowConfig {
+    width: NonZeroU32,
+    height: NonZeroU32,
+    title: String,
+    transparent: bool,
+    level: window::WindowLevel,
+    visible: bool, // only hidden by init_hidden_window_and_context
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: WIDTH,
+            height: HEIGHT,
+            title: String::from("OpenGL tutorial"),
+            transparent: false,
+            level: window::WindowLevel::Normal,
+            visible: true,
+        }
+    }
+}
+
+impl WindowConfig {
+    /// Initialize config with the default options: an opaque 800x800 window at the normal level.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the initial size of the window, in physical pixels.
+    pub fn with_size(self, width: NonZeroU32, height: NonZeroU32) -> Self {
+        Self {
+            width,
+            height,
+            ..self
+        }
+    }
+
+    /// Set the window title.
+    pub fn with_title(self, title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..self
+        }
+    }
+
+    /// Set whether the framebuffer's alpha channel is used to blend the window with whatever is behind it.
+    ///
+    /// This is only honored where the platform supports it, otherwise a warning is printed and the window stays opaque.
+    /// Note the clear color's alpha then matters, e.g. clearing to `(0, 0, 0, 0)` makes the background see-through.
+    pub fn with_transparency(self, transparent: bool) -> Self {
+        Self {
+            transparent,
+            ..self
+        }
+    }
+
+    /// Set whether the window is kept above (or below) other windows,
+    /// e.g. [`WindowLevel::AlwaysOnTop`](window::WindowLevel::AlwaysOnTop) to keep a small render floating over an editor.
+    pub fn with_window_level(self, level: window::WindowLevel) -> Self {
+        Self { level, ..self }
+    }
+
+    fn window_attributes(&self) -> window::WindowAttributes {
+        window::Window::default_attributes()
+            .with_inner_size(dpi::PhysicalSize::new(self.width.get(), self.height.get()))
+            .with_title(self.title.as_str())
+            .with_transparent(self.transparent)
+            .with_window_level(self.level)
+            .with_visible(self.visible)
+    }
+}
+
+/// Initializes a window with an OpenGL context, using the default [`WindowConfig`].
+///
+/// # Safety
+///
+/// See [`init_window_and_context_with()`].
+pub unsafe fn init_window_and_context() -> Result<
+    (
+        event_loop::EventLoop<()>,
+        window::Window,
+        glutin::context::PossiblyCurrentContext,
+        surface::Surface<surface::WindowSurface>,
+    ),
+    Box<dyn std::error::Error>,
+> {
+    // SAFETY: same contract as this function
+    unsafe { init_window_and_context_with(&WindowConfig::new()) }
+}
+
+/// Initializes a window configured by `config` with an OpenGL context.
 ///
 /// # Safety
 ///
@@ -23,7 +121,9 @@ const HEIGHT: NonZeroU32 = unsafe { NonZeroU32::new_unchecked(800) };
 /// returned by this method.
 ///
 /// This is because those objects may rely on the `RawWindowHandle` generated by that `Window`.
-pub unsafe fn init_window_and_context() -> Result<
+pub unsafe fn init_window_and_context_with(
+    config: &WindowConfig,
+) -> Result<
     (
         event_loop::EventLoop<()>,
         window::Window,
@@ -35,16 +135,71 @@ pub unsafe fn init_window_and_context() -> Result<
     let event_loop = event_loop::EventLoop::new().context("failed to create event loop")?;
     event_loop.set_control_flow(event_loop::ControlFlow::Wait);
 
-    let window_attr = window::Window::default_attributes()
-        .with_inner_size(dpi::PhysicalSize::new(WIDTH.get(), HEIGHT.get()))
-        .with_title("OpenGL tutorial");
-    let template_builder = glutin::config::ConfigTemplateBuilder::new().with_alpha_size(8);
-    let (window, gl_config) = glutin_winit::DisplayBuilder::new()
-        .with_window_attributes(Some(window_attr))
-        .build(&event_loop, template_builder, gl_config_picker)?;
+    // SAFETY: see function documentation above
+    let (window, gl_context, surface) = unsafe { create_window_and_context(&event_loop, config)? };
+
+    Ok((event_loop, window, gl_context, surface))
+}
+
+/// Initializes an OpenGL context on an invisible window, for rendering without any on-screen output
+/// (e.g. when running automated tests).
+///
+/// This is not a truly headless context: a real (hidden) window is created since the context needs a surface to render to,
+/// so a display server is still required.
+/// Unlike [`init_window_and_context()`], it takes an existing [`EventLoop`](event_loop::EventLoop)
+/// so it can be called repeatedly (only one event loop can exist per process).
+///
+/// # Safety
+///
+/// Same as [`init_window_and_context_with()`].
+pub unsafe fn init_hidden_window_and_context(
+    event_loop: &event_loop::EventLoop<()>,
+) -> Result<
+    (
+        window::Window,
+        glutin::context::PossiblyCurrentContext,
+        surface::Surface<surface::WindowSurface>,
+    ),
+    Box<dyn std::error::Error>,
+> {
+    let config = WindowConfig {
+        visible: false,
+        ..WindowConfig::new().with_title("OpenGL tutorial (hidden)")
+    };
+    // SAFETY: see function documentation above
+    unsafe { create_window_and_context(event_loop, &config) }
+}
+
+/// Shared implementation of [`init_window_and_context_with()`] and [`init_hidden_window_and_context()`].
+///
+/// # Safety
+///
+/// Same as [`init_window_and_context_with()`].
+unsafe fn create_window_and_context(
+    event_loop: &event_loop::EventLoop<()>,
+    config: &WindowConfig,
+) -> Result<
+    (
+        window::Window,
+        glutin::context::PossiblyCurrentContext,
+        surface::Surface<surface::WindowSurface>,
+    ),
+    Box<dyn std::error::Error>,
+> {
+    // others (e.g. GLX and EGL) drop every config lacking transparency when it is requested,
+    // which may leave none at all, so fall back to an opaque window in that case
+    let (window, gl_config) = match build_display(event_loop, config, config.transparent) {
+        Err(e) if config.transparent && e.is::<NoMatchingConfig>() => {
+            build_display(event_loop, config, false)?
+        }
+        result => result?,
+    };
     let window = window.ok_or(anyhow!(
         "window not initialized immediately, may need finalize_window for this platform"
     ))?;
+    if config.transparent && !gl_config.supports_transparency().unwrap_or(false) {
+        eprintln!("warning: transparent framebuffers are not supported on this platform, window will be opaque");
+    }
     let raw_window_handle = window
         .window_handle()
         .context("failed to obtain raw window handle")?
@@ -63,7 +218,7 @@ pub unsafe fn init_window_and_context() -> Result<
 
     let surface_attrs = surface::SurfaceAttributesBuilder::<surface::WindowSurface>::new()
         .with_single_buffer(false)
-        .build(raw_window_handle, WIDTH, HEIGHT);
+        .build(raw_window_handle, config.width, config.height);
     // SAFETY: see function documentation above
     let surface = unsafe {
         gl_display
@@ -94,18 +249,60 @@ pub unsafe fn init_window_and_context() -> Result<
         gl_display.get_proc_address(&cstr)
     });
 
-    Ok((event_loop, window, gl_context, surface))
+    // compile the error material up front, so a broken fallback shader is caught at startup
+    glutil::GlProgram::compile_fallback();
+
+    Ok((window, gl_context, surface))
+}
+
+/// Creates the display and window, choosing a config which supports transparency if `transparent` is set.
+///
+/// Returns [`NoMatchingConfig`] if the platform has no config matching the template.
+fn build_display(
+    event_loop: &event_loop::EventLoop<()>,
+    config: &WindowConfig,
+    transparent: bool,
+) -> Result<(Option<window::Window>, glutin::config::Config), Box<dyn std::error::Error>> {
+    // some platforms (e.g. CGL on MacOS) only return transparent configs if the template asks for them
+    let template_builder = glutin::config::ConfigTemplateBuilder::new()
+        .with_alpha_size(8)
+        .with_transparency(transparent);
+    let display_builder = glutin_winit::DisplayBuilder::new()
+        .with_window_attributes(Some(config.window_attributes()));
+
+    // the picker has to return a config, so it can only report an empty list by unwinding
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        display_builder.build(event_loop, template_builder, |configs| {
+            gl_config_picker(configs, transparent)
+        })
+    }))
+    .unwrap_or_else(|payload| {
+        if payload.is::<NoMatchingConfig>() {
+            Err(Box::new(NoMatchingConfig))
+        } else {
+            panic::resume_unwind(payload)
+        }
+    })
 }
 
+/// Error returned by [`build_display()`] when no config matches the template.
+#[derive(Debug, thiserror::Error)]
+#[error("no OpenGL config matches the requested window attributes")]
+struct NoMatchingConfig;
+
 // Copied from https://github.com/rust-windowing/glutin/blob/master/glutin_examples/src/lib.rs
-/// Selects the config with the highest sample count which supports transparency
+/// Selects the config with the highest sample count, preferring ones which support transparency if `transparent` is set
+///
+/// Unwinds with [`NoMatchingConfig`] (without invoking the panic hook) if `configs` is empty.
 fn gl_config_picker(
     configs: Box<dyn Iterator<Item = glutin::config::Config> + '_>,
+    transparent: bool,
 ) -> glutin::config::Config {
     configs
         .reduce(|accum, config| {
-            let transparency_check = config.supports_transparency().unwrap_or(false)
-                & !accum.supports_transparency().unwrap_or(false);
+            let transparency_check = transparent
+                && config.supports_transparency().unwrap_or(false)
+                && !accum.supports_transparency().unwrap_or(false);
 
             if transparency_check || config.num_samples() > accum.num_samples() {
                 config
@@ -113,5 +310,5 @@ fn gl_config_picker(
                 accum
             }
         })
-        .expect("at least one matching GL config should exist")
+        .unwrap_or_else(|| panic::resume_unwind(Box::new(NoMatchingConfig)))
 }
@@ -0,0 +1,264 @@
+//! Contains minimal vector/matrix helpers for building the transforms used by the examples.
+//!
+//! Matrices are stored as flat arrays in column-major order,
+//! so they can be passed directly to `glUniformMatrix4fv` with `transpose` set to `GL_FALSE`.
+
+/// A 3-component vector.
+pub type Vec3 = [f32; 3];
+
+/// A 4x4 matrix in column-major order.
+pub type Mat4 = [f32; 16];
+
+/// The 4x4 identity matrix.
+#[rustfmt::skip]
+pub const IDENTITY: Mat4 = [
+    1.0, 0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0, 0.0,
+    0.0, 0.0, 1.0, 0.0,
+    0.0, 0.0, 0.0, 1.0,
+];
+
+/// Component-wise difference `a - b`.
+#[inline]
+pub fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Component-wise sum `a + b`.
+#[inline]
+pub fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+/// Dot product of two vectors.
+#[inline]
+pub fn dot(a: Vec3, b: Vec3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Cross product `a x b`.
+#[inline]
+pub fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Scales `v` to unit length.
+#[inline]
+pub fn normalize(v: Vec3) -> Vec3 {
+    let len = dot(v, v).sqrt();
+    [v[0] / len, v[1] / len, v[2] / len]
+}
+
+/// Multiplies two matrices, returning `a * b`.
+pub fn mul(a: &Mat4, b: &Mat4) -> Mat4 {
+    let mut result = [0.0; 16];
+    for col in 0..4 {
+        for row in 0..4 {
+            result[col * 4 + row] = (0..4).map(|k| a[k * 4 + row] * b[col * 4 + k]).sum();
+        }
+    }
+    result
+}
+
+/// Builds a transform which scales by `scale`, then rotates by the unit quaternion `rotation` (as `[x, y, z, w]`),
+/// then translates by `translation`.
+#[rustfmt::skip]
+pub fn translate_rotate_scale(translation: Vec3, rotation: [f32; 4], scale: Vec3) -> Mat4 {
+    let [x, y, z, w] = rotation;
+    let [sx, sy, sz] = scale;
+    let [tx, ty, tz] = translation;
+
+    [
+        (1.0 - 2.0 * (y * y + z * z)) * sx, 2.0 * (x * y + w * z) * sx, 2.0 * (x * z - w * y) * sx, 0.0,
+        2.0 * (x * y - w * z) * sy, (1.0 - 2.0 * (x * x + z * z)) * sy, 2.0 * (y * z + w * x) * sy, 0.0,
+        2.0 * (x * z + w * y) * sz, 2.0 * (y * z - w * x) * sz, (1.0 - 2.0 * (x * x + y * y)) * sz, 0.0,
+        tx, ty, tz, 1.0,
+    ]
+}
+
+/// Builds a right-handed view matrix looking from `eye` towards `target` (equivalent to `gluLookAt`).
+#[rustfmt::skip]
+pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Mat4 {
+    let f = normalize(sub(target, eye));
+    let s = normalize(cross(f, up));
+    let u = cross(s, f);
+
+    [
+        s[0], u[0], -f[0], 0.0,
+        s[1], u[1], -f[1], 0.0,
+        s[2], u[2], -f[2], 0.0,
+        -dot(s, eye), -dot(u, eye), dot(f, eye), 1.0,
+    ]
+}
+
+/// Builds a perspective projection matrix (equivalent to `gluPerspective`).
+///
+/// `fovy` is the vertical field of view in radians.
+#[rustfmt::skip]
+pub fn perspective(fovy: f32, aspect: f32, near: f32, far: f32) -> Mat4 {
+    let f = 1.0 / (fovy / 2.0).tan();
+    let depth = near - far;
+
+    [
+        f / aspect, 0.0, 0.0, 0.0,
+        0.0, f, 0.0, 0.0,
+        0.0, 0.0, (far + near) / depth, -1.0,
+        0.0, 0.0, 2.0 * far * near / depth, 0.0,
+    ]
+}
+
+/// Look directions and up vectors for each cubemap face,
+/// in the order `+X, -X, +Y, -Y, +Z, -Z` (i.e. `GL_TEXTURE_CUBE_MAP_POSITIVE_X + i`).
+///
+/// The up vectors follow the OpenGL cubemap convention, where face images are stored with the origin at the top-left.
+const CUBE_FACE_DIRECTIONS: [(Vec3, Vec3); 6] = [
+    ([1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    ([-1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+    ([0.0, -1.0, 0.0], [0.0, 0.0, -1.0]),
+    ([0.0, 0.0, 1.0], [0.0, -1.0, 0.0]),
+    ([0.0, 0.0, -1.0], [0.0, -1.0, 0.0]),
+];
+
+/// Builds the view matrices for rendering each face of a cubemap centered at `eye`.
+///
+/// The faces are in the order `+X, -X, +Y, -Y, +Z, -Z`.
+pub fn cube_face_views(eye: Vec3) -> [Mat4; 6] {
+    CUBE_FACE_DIRECTIONS.map(|(dir, up)| look_at(eye, add(eye, dir), up))
+}
+
+/// Builds the projection matrix shared by all cubemap faces, i.e. a square frustum with a 90 degree field of view.
+pub fn cube_face_projection(near: f32, far: f32) -> Mat4 {
+    perspective(std::f32::consts::FRAC_PI_2, 1.0, near, far)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    /// Multiplies `m` by the column vector `v`.
+    fn transform(m: &Mat4, v: [f32; 4]) -> [f32; 4] {
+        std::array::from_fn(|row| (0..4).map(|k| m[k * 4 + row] * v[k]).sum())
+    }
+
+    fn transform_point(m: &Mat4, p: Vec3) -> Vec3 {
+        let [x, y, z, w] = transform(m, [p[0], p[1], p[2], 1.0]);
+        [x / w, y / w, z / w]
+    }
+
+    fn transform_dir(m: &Mat4, d: Vec3) -> Vec3 {
+        let [x, y, z, _] = transform(m, [d[0], d[1], d[2], 0.0]);
+        [x, y, z]
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert!(
+            actual
+                .iter()
+                .zip(expected)
+                .all(|(a, e)| (a - e).abs() < EPSILON),
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn mul_applies_right_operand_first() {
+        let scale = translate_rotate_scale([0.0; 3], [0.0, 0.0, 0.0, 1.0], [2.0, 2.0, 2.0]);
+        let translate = translate_rotate_scale([1.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0], [1.0; 3]);
+
+        assert_eq!(mul(&IDENTITY, &scale), scale);
+        assert_eq!(mul(&scale, &IDENTITY), scale);
+        assert_close(
+            &transform_point(&mul(&translate, &scale), [1.0, 0.0, 0.0]),
+            &[3.0, 0.0, 0.0],
+        );
+        assert_close(
+            &transform_point(&mul(&scale, &translate), [1.0, 0.0, 0.0]),
+            &[4.0, 0.0, 0.0],
+        );
+    }
+
+    #[test]
+    fn translate_rotate_scale_order() {
+        // quarter turn about z, mapping +x onto +y
+        let quarter = std::f32::consts::FRAC_1_SQRT_2;
+        let m = translate_rotate_scale([0.0, 0.0, 5.0], [0.0, 0.0, quarter, quarter], [2.0; 3]);
+        assert_close(&transform_point(&m, [1.0, 0.0, 0.0]), &[0.0, 2.0, 5.0]);
+        assert_close(&transform_point(&m, [0.0, 1.0, 0.0]), &[-2.0, 0.0, 5.0]);
+    }
+
+    #[test]
+    fn look_at_maps_target_to_negative_z() {
+        let eye = [1.0, 2.0, 3.0];
+        let view = look_at(eye, [1.0, 2.0, -7.0], [0.0, 1.0, 0.0]);
+        assert_close(&transform_point(&view, eye), &[0.0, 0.0, 0.0]);
+        assert_close(
+            &transform_point(&view, [1.0, 2.0, -7.0]),
+            &[0.0, 0.0, -10.0],
+        );
+        assert_close(&transform_dir(&view, [0.0, 1.0, 0.0]), &[0.0, 1.0, 0.0]);
+        assert_close(&transform_dir(&view, [1.0, 0.0, 0.0]), &[1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn perspective_maps_near_and_far_planes() {
+        let (near, far) = (0.5, 20.0);
+        let projection = perspective(std::f32::consts::FRAC_PI_2, 2.0, near, far);
+        assert_close(
+            &transform_point(&projection, [0.0, 0.0, -near]),
+            &[0.0, 0.0, -1.0],
+        );
+        assert_close(
+            &transform_point(&projection, [0.0, 0.0, -far]),
+            &[0.0, 0.0, 1.0],
+        );
+        // a 90 degree field of view reaches the top edge at y = -z, and the right edge at x = -z * aspect
+        assert_close(
+            &transform_point(&projection, [4.0, 2.0, -2.0])[..2],
+            &[1.0, 1.0],
+        );
+    }
+
+    #[test]
+    fn cube_face_views_follow_opengl_convention() {
+        // (look direction, up, right) of each face in world space, from the cubemap face selection table of the
+        // OpenGL specification, given that rendering maps window x to the s and window y to the t texture coordinate
+        let expected: [(Vec3, Vec3, Vec3); 6] = [
+            ([1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, -1.0]),
+            ([-1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, 1.0]),
+            ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]),
+            ([0.0, -1.0, 0.0], [0.0, 0.0, -1.0], [1.0, 0.0, 0.0]),
+            ([0.0, 0.0, 1.0], [0.0, -1.0, 0.0], [1.0, 0.0, 0.0]),
+            ([0.0, 0.0, -1.0], [0.0, -1.0, 0.0], [-1.0, 0.0, 0.0]),
+        ];
+
+        let eye = [3.0, -1.0, 2.0];
+        let views = cube_face_views(eye);
+        for (view, (dir, up, right)) in views.iter().zip(expected) {
+            assert_close(&transform_point(view, add(eye, dir)), &[0.0, 0.0, -1.0]);
+            assert_close(&transform_dir(view, up), &[0.0, 1.0, 0.0]);
+            assert_close(&transform_dir(view, right), &[1.0, 0.0, 0.0]);
+        }
+    }
+
+    #[test]
+    fn cube_face_projection_is_square_with_right_angle_fov() {
+        let projection = cube_face_projection(0.1, 10.0);
+        assert_close(
+            &transform_point(&projection, [1.0, 1.0, -1.0])[..2],
+            &[1.0, 1.0],
+        );
+        assert_close(
+            &transform_point(&projection, [-1.0, -1.0, -1.0])[..2],
+            &[-1.0, -1.0],
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Smoke tests which render each example for a fixed number of frames on a hidden window,
+//! checking that nothing panics, no OpenGL errors are raised and no shader is replaced by the fallback program.
+//!
+//! A display server is still required to create the window, so the run fails when none is available.
+//! Machines without one (e.g. CI runners) must opt out explicitly by setting `GLTUT_SKIP_SMOKE=1`,
+//! in which case the run is reported as skipped.
+//!
+//! This does not compare any rendered images, it only catches regressions in the shared framework.
+//! It runs without the default test harness since winit only allows one event loop per process,
+//! which must also live on the main thread on some platforms.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use gltut::app::{GlApp, GlAppDelegate};
+use gltut::glutil::GlProgram;
+use winit::event_loop::EventLoop;
+
+#[allow(dead_code)]
+#[path = "../examples/01-hello-triangle/main.rs"]
+mod hello_triangle;
+
+#[allow(dead_code)]
+#[path = "../examples/02-playing-with-colors/main.rs"]
+mod playing_with_colors;
+
+#[allow(dead_code)]
+#[path = "../examples/03_1-moving-triangle-cpu/main.rs"]
+mod moving_triangle_cpu;
+
+#[allow(dead_code)]
+#[path = "../examples/03_2-moving-triangle-gpu/main.rs"]
+mod moving_triangle_gpu;
+
+#[allow(dead_code)]
+#[path = "../examples/scene-from-file/main.rs"]
+mod scene_from_file;
+
+/// How many frames to render for each example.
+const FRAMES: usize = 120;
+/// Fixed time advanced per frame, so animated examples are deterministic.
+const TIMESTEP: Duration = Duration::from_millis(16);
+
+/// Environment variable which skips the smoke tests when set to `1`, for machines without a display.
+const SKIP_VAR: &str = "GLTUT_SKIP_SMOKE";
+
+fn main() -> ExitCode {
+    if std::env::var(SKIP_VAR).is_ok_and(|value| value == "1") {
+        println!("\nsmoke test result: skipped ({} is set)", SKIP_VAR);
+        return ExitCode::SUCCESS;
+    }
+
+    let event_loop = match EventLoop::new() {
+        Ok(event_loop) => event_loop,
+        Err(e) => {
+            println!(
+                "\nsmoke test result: FAILED\n  failed to create event loop: {}\n  \
+                 set {}=1 to skip the smoke tests on machines without a display",
+                e, SKIP_VAR
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let results = [
+        smoke_test(&event_loop, "01-hello-triangle", hello_triangle::scene),
+        smoke_test(
+            &event_loop,
+            "02-playing-with-colors",
+            playing_with_colors::scene,
+        ),
+        smoke_test(
+            &event_loop,
+            "03_1-moving-triangle-cpu",
+            moving_triangle_cpu::scene,
+        ),
+        smoke_test(
+            &event_loop,
+            "03_2-moving-triangle-gpu",
+            moving_triangle_gpu::scene,
+        ),
+        smoke_test(&event_loop, "scene-from-file", scene_from_file::scene),
+    ];
+
+    let failed = results.iter().filter(|passed| !**passed).count();
+    println!(
+        "\nsmoke test result: {}. {} passed; {} failed",
+        if failed == 0 { "ok" } else { "FAILED" },
+        results.len() - failed,
+        failed
+    );
+
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Renders the scene built by `scene` on a fresh hidden window, returning whether it succeeded.
+fn smoke_test<T, F>(event_loop: &EventLoop<()>, name: &str, scene: F) -> bool
+where
+    T: GlAppDelegate,
+    F: FnOnce() -> T,
+{
+    print!("test {} ... ", name);
+
+    // SAFETY: the window is moved into the GlApp along with the context and surface, so it is dropped with them
+    let (window, gl_context, surface) =
+        match unsafe { gltut::init_hidden_window_and_context(event_loop) } {
+            Ok(handles) => handles,
+            Err(e) => {
+                println!("FAILED\n  failed to create window and context: {}", e);
+                return false;
+            }
+        };
+
+    // discard substitutions made by previous tests, so they are not reported again
+    GlProgram::take_fallback_substituted();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut app = GlApp::new(scene(), window, gl_context, surface)
+            .with_fixed_timestep(TIMESTEP)
+            // never block on a window which is never presented
+            .with_vsync(false);
+        app.render_frames(FRAMES)
+    }));
+
+    match result {
+        // the fallback renders without raising errors, so shader failures would otherwise go unnoticed
+        Ok(Ok(())) if GlProgram::take_fallback_substituted() => {
+            println!("FAILED\n  a shader failed to build, the fallback program was substituted");
+            false
+        }
+        Ok(Ok(())) => {
+            println!("ok");
+            true
+        }
+        Ok(Err(e)) => {
+            println!("FAILED\n  {}", e);
+            false
+        }
+        Err(_) => {
+            // the panic message has already been printed by the panic hook
+            println!("FAILED\n  panicked");
+            false
+        }
+    }
+}